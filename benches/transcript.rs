@@ -0,0 +1,108 @@
+//! Time to read the last turn as a transcript grows. The tail reader should
+//! stay flat while a full front-to-back scan grows with the file, including
+//! when the turn holds a single very long line such as a huge tool result.
+//!
+//! Run with `cargo bench --bench transcript`.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use voice_notifier::transcript::{read_entries, TranscriptFormat};
+
+const SIZES_MB: [u64; 4] = [1, 10, 50, 100];
+const RUNS: usize = 5;
+
+/// The lines of one prompt, tool call, tool result and reply turn, with
+/// `output` as the tool result.
+fn turn_lines(turn: usize, output: &str) -> [String; 4] {
+    [
+        format!(
+            r#"{{"type":"user","uuid":"u{turn}","timestamp":"2025-06-01T10:00:00Z","message":{{"role":"user","content":"Task number {turn}"}}}}"#
+        ),
+        format!(
+            r#"{{"type":"assistant","uuid":"a{turn}","parentUuid":"u{turn}","timestamp":"2025-06-01T10:00:05Z","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"t{turn}","name":"Bash","input":{{"command":"cargo test"}}}}]}}}}"#
+        ),
+        format!(
+            r#"{{"type":"user","uuid":"r{turn}","parentUuid":"a{turn}","timestamp":"2025-06-01T10:00:09Z","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t{turn}","content":"{output}"}}]}}}}"#
+        ),
+        format!(
+            r#"{{"type":"assistant","uuid":"d{turn}","parentUuid":"r{turn}","timestamp":"2025-06-01T10:00:12Z","message":{{"role":"assistant","content":[{{"type":"text","text":"Finished task {turn}, all tests pass."}}]}}}}"#
+        ),
+    ]
+}
+
+/// Write a session of turns until the file reaches `bytes`. With
+/// `long_line`, the last turn's tool result makes up half the file.
+fn write_transcript(path: &Path, bytes: u64, long_line: bool) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let output = "test result: ok. ".repeat(40);
+    let target = if long_line { bytes / 2 } else { bytes };
+    let mut written = 0;
+    let mut turn = 0;
+    while written < target {
+        for line in turn_lines(turn, &output) {
+            writeln!(writer, "{line}")?;
+            written += line.len() as u64 + 1;
+        }
+        turn += 1;
+    }
+    if long_line {
+        let output = "test result: ok. ".repeat((bytes / 2) as usize / 17);
+        for line in turn_lines(turn, &output) {
+            writeln!(writer, "{line}")?;
+        }
+    }
+    writer.flush()
+}
+
+/// Fastest of `RUNS` runs, to keep noise out of the comparison.
+fn fastest(mut run: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let started = Instant::now();
+            run();
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    println!(
+        "{:>8}  {:>10}  {:>12}  {:>12}",
+        "size", "fixture", "last turn", "full scan"
+    );
+
+    for mb in SIZES_MB {
+        for long_line in [false, true] {
+            let path = dir
+                .path()
+                .join(format!("transcript_{mb}mb_{long_line}.jsonl"));
+            write_transcript(&path, mb * 1024 * 1024, long_line)?;
+
+            // What the hook does: detect the agent, then read its last turn
+            let tail = fastest(|| {
+                let format = TranscriptFormat::detect(&path).expect("transcript is readable");
+                assert_eq!(format, TranscriptFormat::ClaudeCode);
+                let turn = format
+                    .read_last_turn(&path)
+                    .expect("transcript is readable");
+                assert!(turn.final_text.is_some(), "transcript has a reply");
+            });
+            let full = fastest(|| {
+                read_entries(&path).expect("transcript is readable");
+            });
+            println!(
+                "{:>6}MB  {:>10}  {:>12}  {:>12}",
+                mb,
+                if long_line { "long line" } else { "turns" },
+                format!("{tail:.2?}"),
+                format!("{full:.2?}")
+            );
+        }
+    }
+    Ok(())
+}
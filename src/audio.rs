@@ -1,251 +0,0 @@
-use anyhow::{Context, Result};
-use std::path::Path;
-use tokio::process::Command;
-use tracing::{debug, error, info};
-
-pub struct AudioPlayer;
-
-impl Default for AudioPlayer {
-    fn default() -> Self {
-        Self
-    }
-}
-
-impl AudioPlayer {
-    pub fn new() -> Self {
-        Self
-    }
-
-    pub async fn is_audio_playing(&self) -> bool {
-        debug!("Checking if audio is already playing...");
-        
-        // Check for running mac process (both afplay and say use this)
-        let mac_check = Command::new("pgrep")
-            .args(&["-x", "mac"])
-            .output()
-            .await;
-
-        match mac_check {
-            Ok(result) => {
-                // pgrep returns 0 if processes found, 1 if not found
-                if result.status.success() {
-                    // Now check if it's specifically an audio-related mac process
-                    let ps_output = Command::new("ps")
-                        .args(&["aux"])
-                        .output()
-                        .await;
-                    
-                    if let Ok(ps_result) = ps_output {
-                        let stdout = String::from_utf8_lossy(&ps_result.stdout);
-                        // Check if mac is running with afplay or say
-                        if stdout.contains("mac afplay") || stdout.contains("mac say") {
-                            info!("Audio processes detected, skipping notification");
-                            return true;
-                        }
-                    }
-                    debug!("mac process found but not audio-related");
-                    false
-                } else {
-                    debug!("No mac processes found via pgrep");
-                    false
-                }
-            }
-            Err(e) => {
-                // If pgrep fails, try alternative method
-                debug!("pgrep failed: {}, trying ps", e);
-                self.check_audio_with_ps().await
-            }
-        }
-    }
-
-    async fn check_audio_with_ps(&self) -> bool {
-        let output = Command::new("ps")
-            .args(&["aux"])
-            .output()
-            .await;
-
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8_lossy(&result.stdout);
-                let has_audio = stdout.contains("mac afplay") || stdout.contains("mac say");
-                if has_audio {
-                    info!("Audio processes detected via ps, skipping notification");
-                }
-                has_audio
-            }
-            Err(e) => {
-                debug!("Failed to check for audio processes: {}", e);
-                // If we can't check, allow playback to avoid blocking notifications entirely
-                false
-            }
-        }
-    }
-
-    pub async fn play_audio_file(&self, file_path: impl AsRef<Path>) -> Result<()> {
-        let path = file_path.as_ref();
-
-        if !path.exists() {
-            return Err(anyhow::anyhow!("Audio file not found: {}", path.display()));
-        }
-
-        info!("Playing audio file: {}", path.display());
-
-        let output = Command::new("mac")
-            .arg("afplay")
-            .arg("-v")
-            .arg("0.5")  // 50% volume
-            .arg(path)
-            .output()
-            .await
-            .context("Failed to execute 'mac afplay' command")?;
-
-        if output.status.success() {
-            debug!("Audio playback completed successfully");
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            error!("Audio playback failed: {}", stderr);
-            Err(anyhow::anyhow!("afplay command failed: {}", stderr))
-        }
-    }
-
-    pub async fn play_audio_file_background(&self, file_path: impl AsRef<Path>) -> Result<()> {
-        let path = file_path.as_ref();
-
-        if !path.exists() {
-            return Err(anyhow::anyhow!("Audio file not found: {}", path.display()));
-        }
-
-        // Check RIGHT before spawning the audio process
-        if self.is_audio_playing().await {
-            info!("Audio already playing, skipping notification");
-            return Ok(());
-        }
-
-        info!("Playing audio file in background: {}", path.display());
-
-        // Spawn the audio player process without waiting for it
-        // -v flag sets volume (0.0 to 1.0, default is 1.0)
-        Command::new("mac")
-            .arg("afplay")
-            .arg("-v")
-            .arg("0.5")  // 50% volume
-            .arg(path)
-            .spawn()
-            .context("Failed to spawn 'mac afplay' command")?;
-
-        debug!("Audio playback started in background");
-        Ok(())
-    }
-
-    #[allow(dead_code)]
-    pub async fn play_with_fallback(
-        &self,
-        file_path: impl AsRef<Path>,
-        fallback_text: &str,
-    ) -> Result<()> {
-        if let Err(e) = self.play_audio_file(file_path).await {
-            debug!(
-                "Audio file playback failed: {}, falling back to 'say' command",
-                e
-            );
-            self.say_text(fallback_text).await?;
-        }
-        Ok(())
-    }
-
-    pub async fn say_text(&self, text: &str) -> Result<()> {
-        info!("Using macOS 'say' command for text: {}", text);
-
-        let output = Command::new("mac")
-            .arg("say")
-            .arg(text)
-            .output()
-            .await
-            .context("Failed to execute 'mac say' command")?;
-
-        if output.status.success() {
-            debug!("Text-to-speech completed successfully");
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("say command failed: {}", stderr))
-        }
-    }
-
-    pub async fn is_system_muted(&self) -> bool {
-        debug!("Checking if system is muted...");
-
-        // Use osascript to check if output is muted
-        let output = Command::new("osascript")
-            .arg("-e")
-            .arg("output muted of (get volume settings)")
-            .output()
-            .await;
-
-        match output {
-            Ok(result) => {
-                if result.status.success() {
-                    let stdout = String::from_utf8_lossy(&result.stdout).trim().to_string();
-                    let is_muted = stdout == "true";
-                    if is_muted {
-                        info!("System is muted");
-                    } else {
-                        debug!("System is not muted");
-                    }
-                    is_muted
-                } else {
-                    debug!("osascript command failed");
-                    false
-                }
-            }
-            Err(e) => {
-                debug!("Failed to check mute status: {}", e);
-                // If we can't check, assume not muted to avoid blocking notifications
-                false
-            }
-        }
-    }
-
-    pub async fn say_text_background(&self, text: &str) -> Result<()> {
-        // Check RIGHT before spawning the say process
-        if self.is_audio_playing().await {
-            info!("Audio already playing, skipping notification");
-            return Ok(());
-        }
-
-        info!("Using macOS 'say' command in background for text: {}", text);
-
-        // Note: 'say' command doesn't have a volume flag, but we can use
-        // osascript to control system volume or use [[volm 0.5]] SSML tag
-        // For now, using say without volume control as fallback
-        Command::new("mac")
-            .arg("say")
-            .arg(text)
-            .spawn()
-            .context("Failed to spawn 'mac say' command")?;
-
-        debug!("Text-to-speech started in background");
-        Ok(())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[tokio::test]
-    async fn test_play_nonexistent_file() {
-        let player = AudioPlayer::new();
-        let result = player.play_audio_file("/nonexistent/file.mp3").await;
-        assert!(result.is_err());
-    }
-
-    #[tokio::test]
-    async fn test_say_text() {
-        let player = AudioPlayer::new();
-        let result = player.say_text("Test").await;
-        // This might fail in CI, so we just check it doesn't panic
-        let _ = result;
-    }
-}
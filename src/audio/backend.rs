@@ -0,0 +1,281 @@
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::tts::AudioFormat;
+
+/// An audio player: an external command or in-process decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBackend {
+    /// Decode and play through the default device (`native-audio` feature)
+    Native,
+    /// Decode without producing sound, for headless machines (`decode` feature)
+    Null,
+    /// `afplay` on the macOS host, reached through OrbStack's `mac` command
+    MacAfplay,
+    Afplay,
+    Paplay,
+    PwPlay,
+    Aplay,
+    Ffplay,
+    Mpv,
+}
+
+impl AudioBackend {
+    /// Auto-detection order; the null sink is only used when requested.
+    pub const ALL: [AudioBackend; 9] = [
+        AudioBackend::Native,
+        AudioBackend::MacAfplay,
+        AudioBackend::Afplay,
+        AudioBackend::Paplay,
+        AudioBackend::PwPlay,
+        AudioBackend::Aplay,
+        AudioBackend::Ffplay,
+        AudioBackend::Mpv,
+        AudioBackend::Null,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.name() == name.trim().to_lowercase())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioBackend::Native => "native",
+            AudioBackend::Null => "null",
+            AudioBackend::MacAfplay => "mac-afplay",
+            AudioBackend::Afplay => "afplay",
+            AudioBackend::Paplay => "paplay",
+            AudioBackend::PwPlay => "pw-play",
+            AudioBackend::Aplay => "aplay",
+            AudioBackend::Ffplay => "ffplay",
+            AudioBackend::Mpv => "mpv",
+        }
+    }
+
+    pub fn is_in_process(&self) -> bool {
+        matches!(self, AudioBackend::Native | AudioBackend::Null)
+    }
+
+    /// Executable looked up on PATH; `None` for in-process backends.
+    pub fn binary(&self) -> Option<&'static str> {
+        match self {
+            AudioBackend::Native | AudioBackend::Null => None,
+            AudioBackend::MacAfplay => Some("mac"),
+            other => Some(other.name()),
+        }
+    }
+
+    /// Find the backend's executable on PATH.
+    pub fn locate(&self) -> Option<PathBuf> {
+        which::which(self.binary()?).ok()
+    }
+
+    /// Whether this build and machine can use the backend.
+    pub fn is_available(&self) -> bool {
+        match self {
+            AudioBackend::Native => cfg!(feature = "native-audio"),
+            AudioBackend::Null => cfg!(feature = "decode"),
+            _ => self.locate().is_some(),
+        }
+    }
+
+    pub fn supported_formats(&self) -> &'static [AudioFormat] {
+        match self {
+            AudioBackend::Native | AudioBackend::Null => {
+                &[AudioFormat::Mp3, AudioFormat::Wav, AudioFormat::Ogg]
+            }
+            AudioBackend::MacAfplay | AudioBackend::Afplay => &[
+                AudioFormat::Mp3,
+                AudioFormat::Wav,
+                AudioFormat::Aac,
+                AudioFormat::Flac,
+            ],
+            // libsndfile based
+            AudioBackend::Paplay | AudioBackend::PwPlay => {
+                &[AudioFormat::Wav, AudioFormat::Flac, AudioFormat::Ogg]
+            }
+            AudioBackend::Aplay => &[AudioFormat::Wav],
+            AudioBackend::Ffplay | AudioBackend::Mpv => &[
+                AudioFormat::Mp3,
+                AudioFormat::Wav,
+                AudioFormat::Ogg,
+                AudioFormat::Opus,
+                AudioFormat::Flac,
+                AudioFormat::Aac,
+            ],
+        }
+    }
+
+    pub fn supports(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(AudioFormat::from_extension)
+            .is_some_and(|format| self.supported_formats().contains(&format))
+    }
+
+    /// Build the playback command; `volume` ranges from 0.0 to 1.0.
+    /// Returns `None` for in-process backends.
+    pub fn play_command(&self, path: &Path, volume: f32) -> Option<Command> {
+        let volume = volume.clamp(0.0, 1.0);
+        let percent = (volume * 100.0).round() as u32;
+
+        let mut command = Command::new(self.binary()?);
+        match self {
+            AudioBackend::Native | AudioBackend::Null => return None,
+            AudioBackend::MacAfplay => {
+                command.arg("afplay").arg("-v").arg(volume.to_string());
+            }
+            AudioBackend::Afplay => {
+                command.arg("-v").arg(volume.to_string());
+            }
+            AudioBackend::Paplay => {
+                // 65536 is 100% for PulseAudio
+                command.arg(format!("--volume={}", (volume * 65536.0).round() as u32));
+            }
+            AudioBackend::PwPlay => {
+                command.arg(format!("--volume={volume}"));
+            }
+            // aplay has no volume control
+            AudioBackend::Aplay => {
+                command.arg("-q");
+            }
+            AudioBackend::Ffplay => {
+                command
+                    .args(["-nodisp", "-autoexit", "-loglevel", "quiet", "-volume"])
+                    .arg(percent.to_string());
+            }
+            AudioBackend::Mpv => {
+                command
+                    .args(["--no-video", "--really-quiet"])
+                    .arg(format!("--volume={percent}"));
+            }
+        }
+        command.arg(path);
+        Some(command)
+    }
+
+    /// Whether a `ps` command line is this backend playing one of our clips,
+    /// a file under `clip_dir`, or our `mac say` fallback, which speaks text
+    /// rather than a file. Players started by anything else don't count.
+    pub fn matches_process(&self, command_line: &str, clip_dir: &Path) -> bool {
+        // With the trailing separator, so `cache` doesn't match `cache-old`
+        let clip_dir = clip_dir.join("");
+        let plays_clip = command_line.contains(&*clip_dir.to_string_lossy());
+
+        let mut words = command_line.split_whitespace();
+        let Some(program) = words.next() else {
+            return false;
+        };
+        let program = Path::new(program)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(program);
+
+        match self {
+            AudioBackend::MacAfplay if program == "mac" => match words.next() {
+                Some("say") => true,
+                Some("afplay") => plays_clip,
+                _ => false,
+            },
+            other => plays_clip && other.binary() == Some(program),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: Option<Command>) -> Vec<String> {
+        command
+            .unwrap()
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_volume_arguments() {
+        let path = Path::new("/tmp/a.wav");
+        assert_eq!(
+            args(AudioBackend::MacAfplay.play_command(path, 0.5)),
+            ["afplay", "-v", "0.5", "/tmp/a.wav"]
+        );
+        assert_eq!(
+            args(AudioBackend::Paplay.play_command(path, 0.5)),
+            ["--volume=32768", "/tmp/a.wav"]
+        );
+        assert_eq!(
+            args(AudioBackend::Mpv.play_command(path, 2.0)),
+            ["--no-video", "--really-quiet", "--volume=100", "/tmp/a.wav"]
+        );
+    }
+
+    #[test]
+    fn test_matches_process() {
+        let dir = Path::new("/home/me/.config/voice-notifier/cache");
+        let matches = |backend: AudioBackend, line: &str| backend.matches_process(line, dir);
+        assert!(matches(
+            AudioBackend::MacAfplay,
+            "mac afplay -v 0.5 /home/me/.config/voice-notifier/cache/temp_voice_notifier_1.mp3"
+        ));
+        assert!(matches(
+            AudioBackend::MacAfplay,
+            "/usr/local/bin/mac say Tests pass"
+        ));
+        assert!(!matches(
+            AudioBackend::MacAfplay,
+            "mac afplay ~/Music/song.mp3"
+        ));
+        assert!(!matches(AudioBackend::Paplay, "mac say hello"));
+        assert!(matches(
+            AudioBackend::Paplay,
+            "/usr/bin/paplay --volume=1 /home/me/.config/voice-notifier/cache/audio/a.wav"
+        ));
+        assert!(!matches(
+            AudioBackend::Paplay,
+            "vim /home/me/.config/voice-notifier/cache/paplay.rs"
+        ));
+        // Someone else's music, or a file outside the clip dir
+        assert!(!matches(AudioBackend::Mpv, "mpv --shuffle ~/Music"));
+        assert!(!matches(
+            AudioBackend::Mpv,
+            "mpv /home/me/.config/voice-notifier/cache-old/a.mp3"
+        ));
+        assert!(!matches(
+            AudioBackend::Null,
+            "null /home/me/.config/voice-notifier/cache/a.wav"
+        ));
+    }
+
+    #[test]
+    fn test_supports_by_extension() {
+        assert!(AudioBackend::Aplay.supports(Path::new("a.wav")));
+        assert!(!AudioBackend::Aplay.supports(Path::new("a.mp3")));
+        assert!(AudioBackend::Mpv.supports(Path::new("a.MP3")));
+        assert!(!AudioBackend::Mpv.supports(Path::new("a")));
+        assert!(AudioBackend::Null.supports(Path::new("a.ogg")));
+    }
+
+    #[test]
+    fn test_in_process_backends_have_no_command() {
+        assert!(AudioBackend::Null
+            .play_command(Path::new("a.wav"), 0.5)
+            .is_none());
+        assert!(AudioBackend::Native.binary().is_none());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            AudioBackend::from_name("pw-play"),
+            Some(AudioBackend::PwPlay)
+        );
+        assert_eq!(AudioBackend::from_name(" MPV "), Some(AudioBackend::Mpv));
+        assert_eq!(AudioBackend::from_name("null"), Some(AudioBackend::Null));
+        assert_eq!(AudioBackend::from_name("winamp"), None);
+    }
+}
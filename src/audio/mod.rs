@@ -0,0 +1,411 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::{Child, Command};
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::tts::AudioFormat;
+
+mod backend;
+#[cfg(feature = "decode")]
+pub mod native;
+
+pub use backend::AudioBackend;
+
+const DEFAULT_VOLUME: f32 = 0.5;
+
+pub struct AudioPlayer {
+    /// Available backends in priority order
+    backends: Vec<AudioBackend>,
+    volume: f32,
+    /// Where our clips are played from; `None` when unknown, in which case
+    /// no running player is taken for one of ours
+    clip_dir: Option<PathBuf>,
+}
+
+/// Playback started by `play_audio_file_background`.
+pub struct Playing {
+    /// `None` when playback was skipped or already finished in-process
+    child: Option<Child>,
+}
+
+impl Playing {
+    /// Wait for the player process to exit, so the file can be removed.
+    pub async fn finished(self) -> Result<()> {
+        let Some(mut child) = self.child else {
+            return Ok(());
+        };
+        let status = child
+            .wait()
+            .await
+            .context("Failed to wait for the audio player")?;
+        if status.success() {
+            debug!("Background audio playback completed");
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Audio player exited with {}", status))
+        }
+    }
+}
+
+impl Default for AudioPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioPlayer {
+    /// Auto-detect every backend found on PATH.
+    pub fn new() -> Self {
+        Self::with_backends(Self::detect_backends(), DEFAULT_VOLUME)
+    }
+
+    pub fn with_backends(backends: Vec<AudioBackend>, volume: f32) -> Self {
+        Self {
+            backends,
+            volume,
+            clip_dir: None,
+        }
+    }
+
+    /// Use `config.audio_backend` if set, otherwise auto-detect.
+    pub fn from_config(config: &Config) -> Self {
+        let backends = match config.audio_backend.as_deref() {
+            Some(name) => match AudioBackend::from_name(name) {
+                Some(backend) => vec![backend],
+                None => {
+                    warn!("Unknown audio backend '{}', auto-detecting", name);
+                    Self::detect_backends()
+                }
+            },
+            None => Self::detect_backends(),
+        };
+        Self {
+            clip_dir: Some(config.cache_dir.clone()),
+            ..Self::with_backends(backends, config.volume)
+        }
+    }
+
+    pub fn detect_backends() -> Vec<AudioBackend> {
+        let backends: Vec<AudioBackend> = AudioBackend::ALL
+            .into_iter()
+            .filter(|backend| *backend != AudioBackend::Null && backend.is_available())
+            .collect();
+        debug!("Detected audio backends: {:?}", backends);
+        backends
+    }
+
+    pub fn backends(&self) -> &[AudioBackend] {
+        &self.backends
+    }
+
+    /// Formats at least one backend can play.
+    pub fn supported_formats(&self) -> Vec<AudioFormat> {
+        let mut formats = Vec::new();
+        for backend in &self.backends {
+            for format in backend.supported_formats() {
+                if !formats.contains(format) {
+                    formats.push(*format);
+                }
+            }
+        }
+        formats
+    }
+
+    /// Backends that can play `path`, in priority order.
+    fn backends_for(&self, path: &Path) -> Result<Vec<AudioBackend>> {
+        let backends: Vec<AudioBackend> = self
+            .backends
+            .iter()
+            .copied()
+            .filter(|backend| backend.supports(path))
+            .collect();
+        if backends.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No audio backend available to play {} (detected: {:?})",
+                path.display(),
+                self.backends
+            ));
+        }
+        Ok(backends)
+    }
+
+    /// Play `path` in-process if the first backend for it runs in-process,
+    /// moving on to the next backend when one fails, e.g. `Native` without
+    /// an output device. Returns the external backend to run otherwise.
+    async fn play_in_process_or_select(&self, path: &Path) -> Result<Option<AudioBackend>> {
+        let mut last_error = None;
+        for backend in self.backends_for(path)? {
+            if !backend.is_in_process() {
+                return Ok(Some(backend));
+            }
+            info!(
+                "Playing audio file with {}: {}",
+                backend.name(),
+                path.display()
+            );
+            match self.play_in_process(backend, path).await {
+                Ok(()) => return Ok(None),
+                Err(e) => {
+                    warn!(
+                        "{} playback failed, trying the next backend: {:#}",
+                        backend.name(),
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| anyhow::anyhow!("No audio backend could play {}", path.display())))
+    }
+
+    /// Whether one of our clips is already playing, started by this or
+    /// another voice-notifier process.
+    pub async fn is_audio_playing(&self) -> bool {
+        debug!("Checking if audio is already playing...");
+        let Some(clip_dir) = &self.clip_dir else {
+            return false;
+        };
+
+        let output = Command::new("ps").args(["-eo", "args="]).output().await;
+
+        match output {
+            Ok(result) => {
+                let stdout = String::from_utf8_lossy(&result.stdout);
+                // The `mac say` fallback runs whichever backends are configured
+                let has_audio = stdout.lines().any(|line| {
+                    self.backends
+                        .iter()
+                        .chain([&AudioBackend::MacAfplay])
+                        .any(|backend| backend.matches_process(line, clip_dir))
+                });
+                if has_audio {
+                    info!("Audio processes detected, skipping notification");
+                }
+                has_audio
+            }
+            Err(e) => {
+                debug!("Failed to check for audio processes: {}", e);
+                // If we can't check, allow playback to avoid blocking notifications entirely
+                false
+            }
+        }
+    }
+
+    pub async fn play_audio_file(&self, file_path: impl AsRef<Path>) -> Result<()> {
+        let path = file_path.as_ref();
+
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Audio file not found: {}", path.display()));
+        }
+
+        let Some(backend) = self.play_in_process_or_select(path).await? else {
+            return Ok(());
+        };
+        info!(
+            "Playing audio file with {}: {}",
+            backend.name(),
+            path.display()
+        );
+
+        let mut command = backend
+            .play_command(path, self.volume)
+            .context("External audio backend has no command")?;
+        let output = command
+            .output()
+            .await
+            .with_context(|| format!("Failed to execute '{}' command", backend.name()))?;
+
+        if output.status.success() {
+            debug!("Audio playback completed successfully");
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            error!("Audio playback failed: {}", stderr);
+            Err(anyhow::anyhow!(
+                "{} command failed: {}",
+                backend.name(),
+                stderr
+            ))
+        }
+    }
+
+    /// Start playback and return without waiting; the player keeps running
+    /// after this process exits unless the caller waits on the result.
+    pub async fn play_audio_file_background(&self, file_path: impl AsRef<Path>) -> Result<Playing> {
+        let path = file_path.as_ref();
+
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Audio file not found: {}", path.display()));
+        }
+
+        // Check RIGHT before spawning the audio process
+        if self.is_audio_playing().await {
+            info!("Audio already playing, skipping notification");
+            return Ok(Playing { child: None });
+        }
+
+        // In-process playback has no process to detach, so it plays to completion
+        let Some(backend) = self.play_in_process_or_select(path).await? else {
+            return Ok(Playing { child: None });
+        };
+        info!(
+            "Playing audio file in background with {}: {}",
+            backend.name(),
+            path.display()
+        );
+
+        let mut command = backend
+            .play_command(path, self.volume)
+            .context("External audio backend has no command")?;
+
+        // Spawn the audio player process without waiting for it
+        let child = command
+            .spawn()
+            .with_context(|| format!("Failed to spawn '{}' command", backend.name()))?;
+
+        debug!("Audio playback started in background");
+        Ok(Playing { child: Some(child) })
+    }
+
+    /// Decode (and for `Native`, play) inside this process; returns once
+    /// playback has finished.
+    #[cfg(feature = "decode")]
+    async fn play_in_process(&self, backend: AudioBackend, path: &Path) -> Result<()> {
+        let path = path.to_path_buf();
+        #[cfg(feature = "native-audio")]
+        let volume = self.volume;
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let audio = native::decode_file(&path)?;
+            let duration = audio.duration();
+            match backend {
+                #[cfg(feature = "native-audio")]
+                AudioBackend::Native => native::play(audio, volume)?,
+                _ => info!("Null sink: decoded {} without playing it", path.display()),
+            }
+            info!("Playback finished ({:?})", duration);
+            Ok(())
+        })
+        .await
+        .context("Audio playback task failed")?
+    }
+
+    #[cfg(not(feature = "decode"))]
+    async fn play_in_process(&self, backend: AudioBackend, _path: &Path) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "The {} audio backend requires building with the `decode` feature",
+            backend.name()
+        ))
+    }
+
+    pub async fn say_text(&self, text: &str) -> Result<()> {
+        info!("Using macOS 'say' command for text: {}", text);
+
+        let output = Command::new("mac")
+            .arg("say")
+            .arg(text)
+            .output()
+            .await
+            .context("Failed to execute 'mac say' command")?;
+
+        if output.status.success() {
+            debug!("Text-to-speech completed successfully");
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(anyhow::anyhow!("say command failed: {}", stderr))
+        }
+    }
+
+    pub async fn is_system_muted(&self) -> bool {
+        debug!("Checking if system is muted...");
+
+        // Use osascript to check if output is muted
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg("output muted of (get volume settings)")
+            .output()
+            .await;
+
+        match output {
+            Ok(result) => {
+                if result.status.success() {
+                    let stdout = String::from_utf8_lossy(&result.stdout).trim().to_string();
+                    let is_muted = stdout == "true";
+                    if is_muted {
+                        info!("System is muted");
+                    } else {
+                        debug!("System is not muted");
+                    }
+                    is_muted
+                } else {
+                    debug!("osascript command failed");
+                    false
+                }
+            }
+            Err(e) => {
+                debug!("Failed to check mute status: {}", e);
+                // If we can't check, assume not muted to avoid blocking notifications
+                false
+            }
+        }
+    }
+
+    pub async fn say_text_background(&self, text: &str) -> Result<()> {
+        // Check RIGHT before spawning the say process
+        if self.is_audio_playing().await {
+            info!("Audio already playing, skipping notification");
+            return Ok(());
+        }
+
+        info!("Using macOS 'say' command in background for text: {}", text);
+
+        // Note: 'say' command doesn't have a volume flag, but we can use
+        // osascript to control system volume or use [[volm 0.5]] SSML tag
+        // For now, using say without volume control as fallback
+        Command::new("mac")
+            .arg("say")
+            .arg(text)
+            .spawn()
+            .context("Failed to spawn 'mac say' command")?;
+
+        debug!("Text-to-speech started in background");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_play_nonexistent_file() {
+        let player = AudioPlayer::new();
+        let result = player.play_audio_file("/nonexistent/file.mp3").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_process_failure_falls_back_to_next_backend() {
+        let file = tempfile::Builder::new().suffix(".wav").tempfile().unwrap();
+        std::fs::write(file.path(), b"not audio").unwrap();
+
+        let player = AudioPlayer::with_backends(vec![AudioBackend::Null, AudioBackend::Mpv], 0.5);
+        assert_eq!(
+            player.play_in_process_or_select(file.path()).await.unwrap(),
+            Some(AudioBackend::Mpv)
+        );
+        let player = AudioPlayer::with_backends(vec![AudioBackend::Null], 0.5);
+        assert!(player.play_in_process_or_select(file.path()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_say_text() {
+        let player = AudioPlayer::new();
+        let result = player.say_text("Test").await;
+        // This might fail in CI, so we just check it doesn't panic
+        let _ = result;
+    }
+}
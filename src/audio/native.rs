@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tracing::debug;
+
+/// Fully decoded interleaved PCM.
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl DecodedAudio {
+    pub fn duration(&self) -> Duration {
+        if self.channels == 0 || self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        let frames = self.samples.len() as f64 / f64::from(self.channels);
+        Duration::from_secs_f64(frames / f64::from(self.sample_rate))
+    }
+}
+
+/// Decode an MP3, WAV or OGG/Vorbis file into memory.
+pub fn decode_file(path: &Path) -> Result<DecodedAudio> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .context("Unsupported audio format")?;
+    let mut format = probed.format;
+
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow::anyhow!("No audio track in {}", path.display()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .context("Unsupported audio codec")?;
+
+    let mut decoded = DecodedAudio {
+        channels: track
+            .codec_params
+            .channels
+            .map(|channels| channels.count() as u16)
+            .unwrap_or(0),
+        sample_rate: track.codec_params.sample_rate.unwrap_or(0),
+        samples: Vec::new(),
+    };
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e).context("Failed to read audio packet"),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(buffer) => {
+                let spec = *buffer.spec();
+                decoded.channels = spec.channels.count() as u16;
+                decoded.sample_rate = spec.rate;
+                let mut samples = SampleBuffer::<f32>::new(buffer.capacity() as u64, spec);
+                samples.copy_interleaved_ref(buffer);
+                decoded.samples.extend_from_slice(samples.samples());
+            }
+            // Corrupt frames are skipped rather than failing the whole clip
+            Err(SymphoniaError::DecodeError(e)) => debug!("Skipping undecodable packet: {}", e),
+            Err(e) => return Err(e).context("Failed to decode audio"),
+        }
+    }
+
+    debug!(
+        "Decoded {} ({} channels, {} Hz, {:?})",
+        path.display(),
+        decoded.channels,
+        decoded.sample_rate,
+        decoded.duration()
+    );
+    Ok(decoded)
+}
+
+/// Play through the default output device, returning once playback finishes.
+#[cfg(feature = "native-audio")]
+pub fn play(audio: DecodedAudio, volume: f32) -> Result<()> {
+    use rodio::{buffer::SamplesBuffer, OutputStream, Sink};
+
+    let (_stream, handle) =
+        OutputStream::try_default().context("No default audio output device")?;
+    let sink = Sink::try_new(&handle).context("Failed to open audio sink")?;
+    sink.set_volume(volume);
+    sink.append(SamplesBuffer::new(
+        audio.channels,
+        audio.sample_rate,
+        audio.samples,
+    ));
+    sink.sleep_until_end();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// One second of 16-bit mono silence at 8 kHz.
+    fn write_wav(path: &Path) {
+        let sample_rate: u32 = 8000;
+        let data_len: u32 = sample_rate * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0);
+        File::create(path).unwrap().write_all(&wav).unwrap();
+    }
+
+    #[test]
+    fn test_decode_wav_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("silence.wav");
+        write_wav(&path);
+
+        let audio = decode_file(&path).unwrap();
+        assert_eq!(audio.channels, 1);
+        assert_eq!(audio.sample_rate, 8000);
+        assert_eq!(audio.duration(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_decode_garbage_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("noise.mp3");
+        std::fs::write(&path, b"not audio").unwrap();
+        assert!(decode_file(&path).is_err());
+    }
+}
@@ -0,0 +1,681 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use tracing::{debug, warn};
+
+use super::{default_config_dir, Config};
+use crate::transcript::Outcome;
+
+/// Per-project overrides, looked up in the project directory.
+pub const PROJECT_FILE_NAME: &str = ".voice-notifier.toml";
+
+/// Where an effective configuration value came from, lowest precedence first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    GlobalFile(PathBuf),
+    ProjectFile(PathBuf),
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::GlobalFile(path) | Source::ProjectFile(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "env {var}"),
+            Source::Cli => write!(f, "--set"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    /// Text with a leading `~/` expanded to the home directory
+    Path,
+    /// Comma-separated and lowercased in env vars
+    List,
+    /// Comma-separated in env vars, case kept
+    Names,
+    Float,
+    /// `true`/`false`, also `1`/`0` and `yes`/`no` in env vars
+    Bool,
+    Integer,
+    /// `Event=value` pairs, comma-separated in env vars
+    EventMap,
+    /// `Event = "text"` tables, only settable in config files
+    TextTable,
+    /// `name = { option = value }` tables of overrides, only settable in
+    /// config files
+    Tables,
+}
+
+/// A configuration key, its env var(s) and how to parse them.
+#[derive(Debug)]
+pub struct ConfigOption {
+    pub key: &'static str,
+    /// Checked in order; the first one set wins
+    pub env: &'static [&'static str],
+    kind: Kind,
+    /// Masked by `config show`
+    pub secret: bool,
+}
+
+const fn option(key: &'static str, env: &'static [&'static str], kind: Kind) -> ConfigOption {
+    ConfigOption {
+        key,
+        env,
+        kind,
+        secret: false,
+    }
+}
+
+const fn secret(key: &'static str, env: &'static [&'static str]) -> ConfigOption {
+    ConfigOption {
+        key,
+        env,
+        kind: Kind::Text,
+        secret: true,
+    }
+}
+
+/// Every option `Config` understands, in `config show` order.
+pub const OPTIONS: &[ConfigOption] = &[
+    option("tts_providers", &["TTS_PROVIDERS"], Kind::List),
+    secret("eleven_labs_api_key", &["ELEVEN_LABS_API_KEY"]),
+    option(
+        "eleven_labs_voice_id",
+        &["ELEVEN_LABS_VOICE_ID"],
+        Kind::Text,
+    ),
+    option(
+        "eleven_labs_model_id",
+        &["ELEVEN_LABS_MODEL_ID"],
+        Kind::Text,
+    ),
+    option(
+        "eleven_labs_base_url",
+        &["ELEVEN_LABS_BASE_URL"],
+        Kind::Text,
+    ),
+    option("piper_binary", &["PIPER_BINARY"], Kind::Text),
+    option("piper_model", &["PIPER_MODEL"], Kind::Path),
+    option("espeak_voice", &["ESPEAK_VOICE"], Kind::Text),
+    option("openai_tts_base_url", &["OPENAI_TTS_BASE_URL"], Kind::Text),
+    secret(
+        "openai_tts_api_key",
+        &["OPENAI_TTS_API_KEY", "OPENAI_API_KEY"],
+    ),
+    option("openai_tts_model", &["OPENAI_TTS_MODEL"], Kind::Text),
+    option("openai_tts_voice", &["OPENAI_TTS_VOICE"], Kind::Text),
+    option("openai_tts_format", &["OPENAI_TTS_FORMAT"], Kind::Text),
+    secret("anthropic_api_key", &["ANTHROPIC_API_KEY"]),
+    option("anthropic_base_url", &["ANTHROPIC_BASE_URL"], Kind::Text),
+    option("summarizer", &["SUMMARIZER"], Kind::Text),
+    option("anthropic_model", &["ANTHROPIC_MODEL"], Kind::Text),
+    option(
+        "openai_chat_base_url",
+        &["OPENAI_CHAT_BASE_URL"],
+        Kind::Text,
+    ),
+    secret(
+        "openai_chat_api_key",
+        &["OPENAI_CHAT_API_KEY", "OPENAI_API_KEY"],
+    ),
+    option("openai_chat_model", &["OPENAI_CHAT_MODEL"], Kind::Text),
+    option("ollama_base_url", &["OLLAMA_BASE_URL"], Kind::Text),
+    option("ollama_model", &["OLLAMA_MODEL"], Kind::Text),
+    option("summary_max_tokens", &["SUMMARY_MAX_TOKENS"], Kind::Integer),
+    option(
+        "summary_timeout_secs",
+        &["SUMMARY_TIMEOUT_SECS"],
+        Kind::Integer,
+    ),
+    option(
+        "summary_cache_ttl_secs",
+        &["SUMMARY_CACHE_TTL_SECS"],
+        Kind::Integer,
+    ),
+    option(
+        "summary_cache_max_entries",
+        &["SUMMARY_CACHE_MAX_ENTRIES"],
+        Kind::Integer,
+    ),
+    option("verbosity", &["SUMMARY_VERBOSITY"], Kind::Text),
+    option("prompts", &[], Kind::TextTable),
+    option("prompt_dir", &["PROMPT_DIR"], Kind::Path),
+    option(
+        "classify_outcome",
+        &["SUMMARY_CLASSIFY_OUTCOME"],
+        Kind::Bool,
+    ),
+    option("project_name", &["NOTIFY_PROJECT_NAME"], Kind::Text),
+    option("announce_stop", &["NOTIFY_ANNOUNCE_STOP"], Kind::Bool),
+    option("audio_backend", &["AUDIO_BACKEND"], Kind::Text),
+    option("volume", &["AUDIO_VOLUME"], Kind::Float),
+    option("earcon", &["NOTIFY_EARCON"], Kind::Path),
+    option("outcomes", &[], Kind::Tables),
+    option("audio_cache_max_mb", &["AUDIO_CACHE_MAX_MB"], Kind::Integer),
+    option("agent_names", &["NOTIFY_AGENT_NAMES"], Kind::Names),
+    option("cooldown_secs", &["NOTIFY_COOLDOWN_SECS"], Kind::Integer),
+    option(
+        "event_cooldown_secs",
+        &["NOTIFY_EVENT_COOLDOWNS"],
+        Kind::EventMap,
+    ),
+    option("max_per_minute", &["NOTIFY_MAX_PER_MINUTE"], Kind::Integer),
+    option("burst_action", &["NOTIFY_BURST_ACTION"], Kind::Text),
+    option("cache_dir", &["CACHE_DIR"], Kind::Path),
+];
+
+/// Options a `.voice-notifier.toml` may set. A cloned repository is not
+/// trusted with binaries, paths or endpoints that would receive API keys.
+const PROJECT_OPTIONS: [&str; 7] = [
+    "project_name",
+    "announce_stop",
+    "eleven_labs_voice_id",
+    "openai_tts_voice",
+    "espeak_voice",
+    "verbosity",
+    "prompts",
+];
+
+fn find_option(key: &str) -> Option<&'static ConfigOption> {
+    OPTIONS.iter().find(|option| option.key == key)
+}
+
+/// Inputs for [`Config::load`] that do not come from the environment.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Replaces `~/.config/voice-notifier/config.toml`; must exist
+    pub config_file: Option<PathBuf>,
+    /// Where the search for `.voice-notifier.toml` starts; parent
+    /// directories are searched too
+    pub project_dir: Option<PathBuf>,
+    /// `key=value` overrides from `--set`
+    pub overrides: Vec<String>,
+}
+
+/// The effective configuration and the layer each value came from.
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: Config,
+    sources: HashMap<String, Source>,
+}
+
+impl LoadedConfig {
+    pub fn source(&self, key: &str) -> &Source {
+        self.sources.get(key).unwrap_or(&Source::Default)
+    }
+
+    /// Effective values as TOML, each annotated with its source.
+    pub fn describe(&self) -> Result<String> {
+        let values = Table::try_from(&self.config).context("Failed to serialize config")?;
+        let mut out = String::new();
+        for option in OPTIONS {
+            let line = match values.get(option.key) {
+                Some(_) if option.secret => format!("{} = \"********\"", option.key),
+                Some(value) => format!("{} = {}", option.key, inline(value)),
+                None => format!("# {} is unset", option.key),
+            };
+            out.push_str(&format!("{:<60} # {}\n", line, self.source(option.key)));
+        }
+        Ok(out)
+    }
+}
+
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Table(table) if table.is_empty() => "{}".to_string(),
+        Value::Table(table) => {
+            let pairs: Vec<String> = table
+                .iter()
+                .map(|(key, value)| format!("{key} = {}", inline(value)))
+                .collect();
+            format!("{{ {} }}", pairs.join(", "))
+        }
+        // Config floats are f32; printing the widened f64 adds noise digits
+        Value::Float(number) => (*number as f32).to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// `~/.config/voice-notifier/config.toml` on Linux.
+pub fn global_config_path() -> PathBuf {
+    default_config_dir().join("config.toml")
+}
+
+impl Config {
+    /// Build the configuration from defaults < global file < project file <
+    /// env vars < `--set` overrides.
+    pub fn load(options: &LoadOptions) -> Result<LoadedConfig> {
+        // Only the user-level .env: hooks run from the project directory, and
+        // a cloned repo's .env could point base URLs or binaries anywhere.
+        // It's ok if it doesn't exist.
+        dotenvy::from_path(default_config_dir().join(".env")).ok();
+
+        let mut layers = Vec::new();
+
+        let global = match &options.config_file {
+            Some(path) if !path.exists() => {
+                anyhow::bail!("Config file not found: {}", path.display())
+            }
+            Some(path) => path.clone(),
+            None => global_config_path(),
+        };
+        if global.exists() {
+            layers.push((read_file(&global)?, Source::GlobalFile(global)));
+        }
+
+        if let Some(path) = options.project_dir.as_deref().and_then(find_project_file) {
+            debug!("Using project config: {}", path.display());
+            layers.push((read_project_file(&path)?, Source::ProjectFile(path)));
+        }
+
+        layers.extend(env_layer(|var| std::env::var(var).ok()));
+
+        let mut cli = Table::new();
+        for assignment in &options.overrides {
+            let (key, value) = parse_override(assignment)?;
+            cli.insert(key, value);
+        }
+        layers.push((cli, Source::Cli));
+
+        resolve(layers)
+    }
+
+    /// This configuration with `[outcomes.<outcome>]` applied on top, e.g. a
+    /// different voice or earcon for failures.
+    pub fn for_outcome(&self, outcome: Outcome) -> Result<Config> {
+        let Some(overrides) = self.outcomes.get(outcome.as_str()) else {
+            return Ok(self.clone());
+        };
+        let mut values = Table::try_from(self).context("Failed to serialize config")?;
+        for (key, value) in overrides {
+            match find_option(key) {
+                Some(option) if option.kind != Kind::Tables => {
+                    let value = match (option.kind, value) {
+                        (Kind::Path, Value::String(text)) => Value::String(expand_home(text)),
+                        _ => value.clone(),
+                    };
+                    values.insert(key.clone(), value);
+                }
+                _ => warn!("Ignoring unknown option '{}' in outcomes.{}", key, outcome),
+            }
+        }
+        Value::Table(values)
+            .try_into()
+            .with_context(|| format!("Invalid configuration in outcomes.{outcome}"))
+    }
+}
+
+/// The nearest `.voice-notifier.toml` in `start` or one of its parents.
+pub fn find_project_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+fn resolve(layers: Vec<(Table, Source)>) -> Result<LoadedConfig> {
+    let mut merged = Table::new();
+    let mut sources = HashMap::new();
+    for (values, source) in layers {
+        for (key, value) in values {
+            sources.insert(key.clone(), source.clone());
+            match (merged.get_mut(&key), value) {
+                // Per-event tables merge key by key
+                (Some(Value::Table(existing)), Value::Table(value)) => existing.extend(value),
+                (_, value) => {
+                    merged.insert(key, value);
+                }
+            }
+        }
+    }
+
+    let config = Value::Table(merged)
+        .try_into()
+        .context("Invalid configuration")?;
+    Ok(LoadedConfig { config, sources })
+}
+
+fn read_file(path: &Path) -> Result<Table> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut table: Table =
+        toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    table.retain(|key, value| match find_option(key) {
+        Some(option) => {
+            if option.kind == Kind::Path {
+                if let Value::String(text) = value {
+                    *text = expand_home(text);
+                }
+            }
+            true
+        }
+        None => {
+            warn!("Ignoring unknown option '{}' in {}", key, path.display());
+            false
+        }
+    });
+    Ok(table)
+}
+
+/// A project file, keeping only the options in `PROJECT_OPTIONS`.
+fn read_project_file(path: &Path) -> Result<Table> {
+    let mut table = read_file(path)?;
+    table.retain(|key, _| {
+        let allowed = PROJECT_OPTIONS.contains(&key);
+        if !allowed {
+            warn!(
+                "Ignoring '{}' in {}: only project names, voices, verbosity and prompts can be set per project",
+                key,
+                path.display()
+            );
+        }
+        allowed
+    });
+    Ok(table)
+}
+
+/// One layer per env var that is set, so each value records its variable.
+fn env_layer(lookup: impl Fn(&str) -> Option<String>) -> Vec<(Table, Source)> {
+    let mut layers = Vec::new();
+    for option in OPTIONS {
+        let Some((var, raw)) = option.env.iter().find_map(|var| Some((*var, lookup(var)?))) else {
+            continue;
+        };
+        match parse_env_value(option.kind, &raw) {
+            Some(value) => {
+                let mut table = Table::new();
+                table.insert(option.key.to_string(), value);
+                layers.push((table, Source::Env(var)));
+            }
+            None => warn!("Ignoring invalid value for {}: {}", var, raw),
+        }
+    }
+    layers
+}
+
+fn parse_env_value(kind: Kind, raw: &str) -> Option<Value> {
+    Some(match kind {
+        Kind::Text => Value::String(raw.to_string()),
+        Kind::Path => Value::String(expand_home(raw)),
+        Kind::List => Value::Array(parse_list(raw).into_iter().map(Value::String).collect()),
+        Kind::Names => Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| Value::String(name.to_string()))
+                .collect(),
+        ),
+        Kind::Float => Value::Float(raw.trim().parse().ok()?),
+        Kind::Bool => Value::Boolean(match raw.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => true,
+            "false" | "0" | "no" => false,
+            _ => return None,
+        }),
+        Kind::Integer => Value::Integer(raw.trim().parse().ok()?),
+        Kind::EventMap => Value::Table(parse_event_map(raw)?),
+        Kind::TextTable | Kind::Tables => return None,
+    })
+}
+
+/// Parse a comma-separated env value such as `TTS_PROVIDERS=elevenlabs,piper`.
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parse per-event values such as `NOTIFY_EVENT_COOLDOWNS=Stop=10,Notification=0`.
+fn parse_event_map(value: &str) -> Option<Table> {
+    value
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            let (event, number) = pair.split_once('=')?;
+            Some((
+                event.trim().to_string(),
+                Value::Integer(number.trim().parse().ok()?),
+            ))
+        })
+        .collect()
+}
+
+/// Parse `--set key=value`; the value is read as TOML and falls back to a
+/// plain string, so `--set volume=0.8` and `--set voice=alloy` both work.
+fn parse_override(assignment: &str) -> Result<(String, Value)> {
+    let (key, raw) = assignment
+        .split_once('=')
+        .with_context(|| format!("Expected key=value, got '{assignment}'"))?;
+    let key = key.trim();
+    let option = find_option(key).with_context(|| format!("Unknown config option '{key}'"))?;
+
+    let value = toml::from_str::<Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()));
+    let value = match (option.kind, value) {
+        (Kind::Path, Value::String(text)) => Value::String(expand_home(&text)),
+        (_, value) => value,
+    };
+    Ok((key.to_string(), value))
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::BurstAction;
+
+    fn file_layer(contents: &str, path: &str) -> (Table, Source) {
+        (
+            toml::from_str(contents).unwrap(),
+            Source::GlobalFile(PathBuf::from(path)),
+        )
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let global = file_layer(
+            "volume = 0.2\nespeak_voice = \"en-gb\"\n[event_cooldown_secs]\nStop = 30\n",
+            "global.toml",
+        );
+        let project = (
+            toml::from_str("volume = 0.4\n").unwrap(),
+            Source::ProjectFile(PathBuf::from(".voice-notifier.toml")),
+        );
+        let env = env_layer(|var| match var {
+            "AUDIO_VOLUME" => Some("0.6".to_string()),
+            "NOTIFY_EVENT_COOLDOWNS" => Some("Notification=0".to_string()),
+            "NOTIFY_BURST_ACTION" => Some("merge".to_string()),
+            _ => None,
+        });
+        let mut cli = Table::new();
+        let (key, value) = parse_override("volume=0.8").unwrap();
+        cli.insert(key, value);
+
+        let mut layers = vec![global, project];
+        layers.extend(env);
+        layers.push((cli, Source::Cli));
+        let loaded = resolve(layers).unwrap();
+
+        assert_eq!(loaded.config.volume, 0.8);
+        assert_eq!(loaded.source("volume"), &Source::Cli);
+        assert_eq!(loaded.config.espeak_voice.as_deref(), Some("en-gb"));
+        assert_eq!(loaded.config.burst_action, BurstAction::Merge);
+        assert_eq!(
+            loaded.source("burst_action"),
+            &Source::Env("NOTIFY_BURST_ACTION")
+        );
+        assert_eq!(loaded.config.event_cooldown_secs["Stop"], 30);
+        assert_eq!(loaded.config.event_cooldown_secs["Notification"], 0);
+        assert_eq!(loaded.source("max_per_minute"), &Source::Default);
+        assert_eq!(loaded.config.max_per_minute, 6);
+    }
+
+    #[test]
+    fn test_outcome_overrides() {
+        let (values, _) = file_layer(
+            "volume = 0.5\n[outcomes.failure]\nvolume = 0.9\nearcon = \"~/sounds/fail.wav\"\nvoice = \"x\"\n",
+            "global.toml",
+        );
+        let config: Config = Value::Table(values).try_into().unwrap();
+
+        let failure = config.for_outcome(Outcome::Failure).unwrap();
+        assert_eq!(failure.volume, 0.9);
+        assert!(failure.earcon.unwrap().ends_with("sounds/fail.wav"));
+        assert_eq!(config.for_outcome(Outcome::Success).unwrap().volume, 0.5);
+    }
+
+    #[test]
+    fn test_env_fallback_and_invalid_values() {
+        let layers = env_layer(|var| match var {
+            "OPENAI_API_KEY" => Some("sk-test".to_string()),
+            "TTS_PROVIDERS" => Some("Piper, espeak".to_string()),
+            "AUDIO_VOLUME" => Some("loud".to_string()),
+            _ => None,
+        });
+        let loaded = resolve(layers).unwrap();
+        assert_eq!(loaded.config.openai_tts_api_key.as_deref(), Some("sk-test"));
+        assert_eq!(
+            loaded.source("openai_tts_api_key"),
+            &Source::Env("OPENAI_API_KEY")
+        );
+        assert_eq!(loaded.config.tts_providers, ["piper", "espeak"]);
+        assert_eq!(loaded.source("volume"), &Source::Default);
+    }
+
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(
+            parse_override("tts_providers=[\"piper\"]").unwrap().1,
+            Value::Array(vec![Value::String("piper".to_string())])
+        );
+        assert_eq!(
+            parse_override("openai_tts_voice=alloy").unwrap().1,
+            Value::String("alloy".to_string())
+        );
+        assert!(parse_override("colour=blue").is_err());
+        assert!(parse_override("volume").is_err());
+    }
+
+    #[test]
+    fn test_find_project_file_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("api/src/handlers");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_project_file(&nested), None);
+
+        let project_file = dir.path().join("api").join(PROJECT_FILE_NAME);
+        std::fs::write(&project_file, "project_name = \"billing API\"\n").unwrap();
+        assert_eq!(find_project_file(&nested), Some(project_file.clone()));
+
+        let loaded = resolve(vec![(
+            read_project_file(&project_file).unwrap(),
+            Source::ProjectFile(project_file),
+        )])
+        .unwrap();
+        assert_eq!(loaded.config.project_name.as_deref(), Some("billing API"));
+        assert!(loaded.config.announce_stop);
+    }
+
+    #[test]
+    fn test_project_file_cannot_set_binaries_or_endpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_file = dir.path().join(PROJECT_FILE_NAME);
+        std::fs::write(
+            &project_file,
+            "piper_binary = \"./evil.sh\"\n\
+             anthropic_base_url = \"https://attacker.example\"\n\
+             eleven_labs_base_url = \"https://attacker.example\"\n\
+             cache_dir = \"/tmp/elsewhere\"\n\
+             eleven_labs_voice_id = \"pNInz6obpgDQGcFmaJgB\"\n",
+        )
+        .unwrap();
+
+        let global = file_layer(
+            "anthropic_base_url = \"https://proxy.internal\"\n",
+            "global.toml",
+        );
+        let project = (
+            read_project_file(&project_file).unwrap(),
+            Source::ProjectFile(project_file),
+        );
+        let loaded = resolve(vec![global, project]).unwrap();
+        assert_eq!(loaded.config.piper_binary, "piper");
+        assert_eq!(loaded.config.anthropic_base_url, "https://proxy.internal");
+        assert_eq!(
+            loaded.config.eleven_labs_base_url,
+            "https://api.elevenlabs.io"
+        );
+        assert_eq!(loaded.config.eleven_labs_voice_id, "pNInz6obpgDQGcFmaJgB");
+    }
+
+    #[test]
+    fn test_project_dotenv_is_not_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".env"),
+            "PIPER_BINARY=./evil.sh\nANTHROPIC_BASE_URL=https://attacker.example\n",
+        )
+        .unwrap();
+        let global = dir.path().join("config.toml");
+        std::fs::write(&global, "").unwrap();
+
+        // Hooks run with the project as the working directory
+        let previous = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        let loaded = Config::load(&LoadOptions {
+            config_file: Some(global),
+            project_dir: Some(dir.path().to_path_buf()),
+            overrides: Vec::new(),
+        });
+        std::env::set_current_dir(previous).unwrap();
+
+        let config = loaded.unwrap().config;
+        assert_ne!(config.piper_binary, "./evil.sh");
+        assert_ne!(config.anthropic_base_url, "https://attacker.example");
+        assert!(std::env::var("PIPER_BINARY").map_or(true, |value| value != "./evil.sh"));
+    }
+
+    #[test]
+    fn test_bool_env_values() {
+        assert_eq!(
+            parse_env_value(Kind::Bool, "no"),
+            Some(Value::Boolean(false))
+        );
+        assert_eq!(
+            parse_env_value(Kind::Bool, "TRUE"),
+            Some(Value::Boolean(true))
+        );
+        assert_eq!(parse_env_value(Kind::Bool, "maybe"), None);
+    }
+
+    #[test]
+    fn test_describe_masks_secrets() {
+        let layers = vec![file_layer(
+            "anthropic_api_key = \"sk-ant-secret\"\n",
+            "/etc/vn.toml",
+        )];
+        let description = resolve(layers).unwrap().describe().unwrap();
+        assert!(!description.contains("sk-ant-secret"));
+        assert!(description.contains("anthropic_api_key = \"********\""));
+        assert!(description.contains("# /etc/vn.toml"));
+        assert!(description.contains("# espeak_voice is unset"));
+    }
+}
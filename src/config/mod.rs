@@ -0,0 +1,354 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::audio::AudioBackend;
+use crate::policy::BurstAction;
+use crate::summarizer::{Verbosity, SUMMARIZER_NAMES};
+use crate::transcript::Outcome;
+use crate::tts::{AudioFormat, PROVIDER_NAMES};
+
+mod layers;
+
+pub use layers::{
+    find_project_file, global_config_path, LoadOptions, LoadedConfig, Source, PROJECT_FILE_NAME,
+};
+
+/// Settings from `config.toml`, `.voice-notifier.toml`, env vars and
+/// `--set`; see [`Config::load`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    #[serde(default)]
+    pub eleven_labs_api_key: Option<String>,
+
+    #[serde(default = "default_voice_id")]
+    pub eleven_labs_voice_id: String,
+
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: PathBuf,
+
+    #[serde(default = "default_model_id")]
+    pub eleven_labs_model_id: String,
+
+    /// ElevenLabs API root, for proxies and stub servers
+    #[serde(default = "default_eleven_labs_base_url")]
+    pub eleven_labs_base_url: String,
+
+    #[serde(default)]
+    pub anthropic_api_key: Option<String>,
+
+    /// Anthropic API root, for proxies and stub servers
+    #[serde(default = "default_anthropic_base_url")]
+    pub anthropic_base_url: String,
+
+    /// Summary backend: "anthropic", "openai", "ollama" or "none"
+    #[serde(default = "default_summarizer")]
+    pub summarizer: String,
+
+    #[serde(default = "default_anthropic_model")]
+    pub anthropic_model: String,
+
+    /// OpenAI-compatible chat server, e.g. llama.cpp or vLLM
+    #[serde(default)]
+    pub openai_chat_base_url: Option<String>,
+
+    #[serde(default)]
+    pub openai_chat_api_key: Option<String>,
+
+    #[serde(default = "default_openai_chat_model")]
+    pub openai_chat_model: String,
+
+    #[serde(default = "default_ollama_base_url")]
+    pub ollama_base_url: String,
+
+    #[serde(default = "default_ollama_model")]
+    pub ollama_model: String,
+
+    /// Upper bound on summary length, shared by all summarizers
+    #[serde(default = "default_summary_max_tokens")]
+    pub summary_max_tokens: u32,
+
+    /// Seconds to wait for a summary before falling back to truncation
+    #[serde(default = "default_summary_timeout_secs")]
+    pub summary_timeout_secs: u64,
+
+    /// How long cached summaries are reused; 0 disables the cache
+    #[serde(default = "default_summary_cache_ttl_secs")]
+    pub summary_cache_ttl_secs: u64,
+
+    /// Summaries kept in the cache, oldest dropped first
+    #[serde(default = "default_summary_cache_max_entries")]
+    pub summary_cache_max_entries: usize,
+
+    /// How long summaries are: terse, normal or detailed
+    #[serde(default)]
+    pub verbosity: Verbosity,
+
+    /// Summary prompt templates per event, e.g. `Stop = "..."`
+    #[serde(default)]
+    pub prompts: HashMap<String, String>,
+
+    /// Directory searched for `<event>.txt` prompt templates
+    #[serde(default = "default_prompt_dir")]
+    pub prompt_dir: PathBuf,
+
+    /// Ask the summarizer to tag how the task ended, refining the heuristic
+    /// outcome
+    #[serde(default)]
+    pub classify_outcome: bool,
+
+    /// TTS providers in fallback order
+    #[serde(default = "default_tts_providers")]
+    pub tts_providers: Vec<String>,
+
+    #[serde(default = "default_piper_binary")]
+    pub piper_binary: String,
+
+    /// Piper ONNX voice model; Piper is skipped without one
+    #[serde(default)]
+    pub piper_model: Option<PathBuf>,
+
+    #[serde(default)]
+    pub espeak_voice: Option<String>,
+
+    /// Base URL of an OpenAI-compatible speech server (defaults to api.openai.com)
+    #[serde(default)]
+    pub openai_tts_base_url: Option<String>,
+
+    #[serde(default)]
+    pub openai_tts_api_key: Option<String>,
+
+    #[serde(default = "default_openai_tts_model")]
+    pub openai_tts_model: String,
+
+    #[serde(default = "default_openai_tts_voice")]
+    pub openai_tts_voice: String,
+
+    #[serde(default = "default_openai_tts_format")]
+    pub openai_tts_format: String,
+
+    /// Force a playback backend (e.g. "mpv") instead of auto-detecting
+    #[serde(default)]
+    pub audio_backend: Option<String>,
+
+    /// Agents whose fixed phrases `cache warm` synthesizes
+    #[serde(default = "default_agent_names")]
+    pub agent_names: Vec<String>,
+
+    /// Size limit of the synthesized speech cache; 0 disables it
+    #[serde(default = "default_audio_cache_max_mb")]
+    pub audio_cache_max_mb: u64,
+
+    /// Playback volume from 0.0 to 1.0
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+
+    /// Sound played before each spoken notification
+    #[serde(default)]
+    pub earcon: Option<PathBuf>,
+
+    /// Option overrides per outcome, e.g. `[outcomes.failure]` with another
+    /// voice or earcon; see [`Config::for_outcome`]
+    #[serde(default)]
+    pub outcomes: HashMap<String, toml::Table>,
+
+    /// Spoken instead of the project directory name
+    #[serde(default)]
+    pub project_name: Option<String>,
+
+    /// Whether Stop events are announced; Notification events always are
+    #[serde(default = "default_true")]
+    pub announce_stop: bool,
+
+    /// Seconds between two notifications of the same event type
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+
+    /// Per-event overrides of `cooldown_secs`, e.g. `Notification=0`
+    #[serde(default)]
+    pub event_cooldown_secs: HashMap<String, u64>,
+
+    /// Notifications allowed per minute across all events; 0 is unlimited
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: u32,
+
+    /// What to do with notifications arriving inside a window
+    #[serde(default = "default_burst_action")]
+    pub burst_action: BurstAction,
+}
+
+fn default_voice_id() -> String {
+    // Default voice ID - can be overridden via env
+    "21m00Tcm4TlvDq8ikWAM".to_string() // Rachel voice
+}
+
+/// Directory holding the cache, hook log and user-level `.env`.
+pub fn default_config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("voice-notifier")
+}
+
+fn default_cache_dir() -> PathBuf {
+    default_config_dir().join("cache")
+}
+
+fn default_model_id() -> String {
+    "eleven_multilingual_v2".to_string()
+}
+
+fn default_tts_providers() -> Vec<String> {
+    vec![
+        "elevenlabs".to_string(),
+        "piper".to_string(),
+        "espeak".to_string(),
+    ]
+}
+
+fn default_piper_binary() -> String {
+    "piper".to_string()
+}
+
+fn default_eleven_labs_base_url() -> String {
+    crate::tts::elevenlabs::DEFAULT_BASE_URL.to_string()
+}
+
+fn default_anthropic_base_url() -> String {
+    crate::summarizer::anthropic::DEFAULT_BASE_URL.to_string()
+}
+
+fn default_summarizer() -> String {
+    "anthropic".to_string()
+}
+
+fn default_anthropic_model() -> String {
+    crate::summarizer::anthropic::DEFAULT_MODEL.to_string()
+}
+
+fn default_openai_chat_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_ollama_base_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_ollama_model() -> String {
+    "llama3.2".to_string()
+}
+
+fn default_summary_max_tokens() -> u32 {
+    100
+}
+
+fn default_summary_timeout_secs() -> u64 {
+    10
+}
+
+fn default_summary_cache_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_summary_cache_max_entries() -> usize {
+    500
+}
+
+fn default_agent_names() -> Vec<String> {
+    vec!["Claude Code".to_string()]
+}
+
+fn default_audio_cache_max_mb() -> u64 {
+    100
+}
+
+fn default_prompt_dir() -> PathBuf {
+    default_config_dir().join("prompts")
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_volume() -> f32 {
+    0.5
+}
+
+fn default_cooldown_secs() -> u64 {
+    10
+}
+
+fn default_max_per_minute() -> u32 {
+    6
+}
+
+fn default_burst_action() -> BurstAction {
+    BurstAction::Defer
+}
+
+fn default_openai_tts_model() -> String {
+    "tts-1".to_string()
+}
+
+fn default_openai_tts_voice() -> String {
+    "alloy".to_string()
+}
+
+fn default_openai_tts_format() -> String {
+    "mp3".to_string()
+}
+
+impl Config {
+    /// Values that load fine but cannot work, such as a misspelt provider.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !(0.0..=1.0).contains(&self.volume) {
+            problems.push(format!("volume {} is outside 0.0 to 1.0", self.volume));
+        }
+        if self.tts_providers.is_empty() {
+            problems.push("tts_providers is empty".to_string());
+        }
+        for name in &self.tts_providers {
+            if !PROVIDER_NAMES.contains(&name.as_str()) {
+                problems.push(format!("unknown TTS provider '{name}'"));
+            }
+        }
+        if let Some(name) = &self.audio_backend {
+            if AudioBackend::from_name(name).is_none() {
+                problems.push(format!("unknown audio backend '{name}'"));
+            }
+        }
+        if !SUMMARIZER_NAMES.contains(&self.summarizer.as_str()) {
+            problems.push(format!("unknown summarizer '{}'", self.summarizer));
+        }
+        for name in self.outcomes.keys() {
+            if name.parse::<Outcome>().is_err() {
+                problems.push(format!("unknown outcome '{name}' in outcomes"));
+            }
+        }
+        if AudioFormat::from_extension(&self.openai_tts_format).is_none() {
+            problems.push(format!(
+                "unsupported openai_tts_format '{}'",
+                self.openai_tts_format
+            ));
+        }
+        problems
+    }
+
+    pub fn ensure_cache_dir(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.cache_dir).context("Failed to create cache directory")?;
+        Ok(())
+    }
+
+    pub fn daemon_socket_path(&self) -> PathBuf {
+        self.cache_dir.join("daemon.sock")
+    }
+
+    pub fn hook_log_path(&self) -> PathBuf {
+        default_config_dir().join("hook.log")
+    }
+
+    pub fn has_eleven_labs_config(&self) -> bool {
+        self.eleven_labs_api_key.is_some()
+    }
+}
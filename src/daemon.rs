@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::queue::NotificationQueue;
+use crate::speaker::{Playback, Speaker};
+use crate::transcript::Outcome;
+
+/// One notification sent by a CLI invocation, as a JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRequest {
+    pub text: String,
+
+    #[serde(default)]
+    pub event: Option<String>,
+
+    pub agent_name: String,
+
+    /// Agent session, used to coalesce repeated Stop summaries
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Project directory name, spoken when summaries are coalesced
+    #[serde(default)]
+    pub project: Option<String>,
+
+    /// How the task ended, selecting `[outcomes.<outcome>]` settings
+    #[serde(default)]
+    pub outcome: Option<Outcome>,
+}
+
+/// The daemon's JSON-line reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DaemonResponse {
+    /// `position` counts the notifications ahead of this one; `coalesced`
+    /// means it was merged into an already queued notification.
+    Queued {
+        position: usize,
+        coalesced: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Hand a notification to a running daemon.
+pub async fn send(socket_path: &Path, request: &NotificationRequest) -> Result<DaemonResponse> {
+    let stream = UnixStream::connect(socket_path)
+        .await
+        .with_context(|| format!("Failed to connect to daemon at {}", socket_path.display()))?;
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.shutdown().await?;
+
+    let mut response = String::new();
+    BufReader::new(reader).read_line(&mut response).await?;
+    serde_json::from_str(&response).context("Invalid daemon response")
+}
+
+/// Whether a daemon is accepting connections on `socket_path`.
+pub async fn is_running(socket_path: &Path) -> bool {
+    UnixStream::connect(socket_path).await.is_ok()
+}
+
+/// Listen on the daemon socket and play queued notifications one at a time
+/// until interrupted.
+pub async fn run(config: Config) -> Result<()> {
+    config.ensure_cache_dir()?;
+    let socket_path = config.daemon_socket_path();
+
+    if socket_path.exists() {
+        if is_running(&socket_path).await {
+            anyhow::bail!("A daemon is already listening on {}", socket_path.display());
+        }
+        debug!("Removing stale socket: {}", socket_path.display());
+        std::fs::remove_file(&socket_path).context("Failed to remove stale socket")?;
+    }
+
+    let listener = UnixListener::bind(&socket_path)
+        .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
+    info!("Daemon listening on {}", socket_path.display());
+
+    let queue = Arc::new(NotificationQueue::default());
+    let speaker = Speaker::new(config);
+
+    let worker_queue = queue.clone();
+    let worker = tokio::spawn(async move {
+        loop {
+            let queued = worker_queue.pop().await;
+            let text = queued.text();
+            info!(
+                "Playing notification ({} still queued): {}",
+                worker_queue.len(),
+                text
+            );
+            let outcome_speaker = queued.request.outcome.and_then(|outcome| {
+                speaker.for_outcome(outcome).unwrap_or_else(|e| {
+                    warn!("Ignoring {} settings: {:#}", outcome, e);
+                    None
+                })
+            });
+            let speaker = outcome_speaker.as_ref().unwrap_or(&speaker);
+            if let Err(e) = speaker.speak(&text, Playback::UntilFinished).await {
+                error!("Failed to play notification: {:#}", e);
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let queue = queue.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &queue).await {
+                            warn!("Daemon connection failed: {:#}", e);
+                        }
+                    });
+                }
+                Err(e) => error!("Failed to accept connection: {}", e),
+            },
+            _ = tokio::signal::ctrl_c() => {
+                info!("Shutting down daemon");
+                break;
+            }
+        }
+    }
+
+    worker.abort();
+    let _ = std::fs::remove_file(&socket_path);
+    Ok(())
+}
+
+async fn handle_connection(stream: UnixStream, queue: &NotificationQueue) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    if BufReader::new(reader).read_line(&mut line).await? == 0 {
+        // `is_running` probes connect and hang up without a request
+        return Ok(());
+    }
+
+    let response = match serde_json::from_str::<NotificationRequest>(&line) {
+        Ok(request) => {
+            debug!("Queued notification: {:?}", request);
+            let enqueued = queue.push(request);
+            DaemonResponse::Queued {
+                position: enqueued.position,
+                coalesced: enqueued.coalesced,
+            }
+        }
+        Err(e) => DaemonResponse::Error {
+            message: format!("Invalid request: {e}"),
+        },
+    };
+
+    let mut reply = serde_json::to_string(&response)?;
+    reply.push('\n');
+    writer.write_all(reply.as_bytes()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(text: &str) -> NotificationRequest {
+        NotificationRequest {
+            text: text.to_string(),
+            event: Some("Stop".to_string()),
+            agent_name: "Claude Code".to_string(),
+            session_id: None,
+            project: None,
+            outcome: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_queued_over_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let queue = Arc::new(NotificationQueue::default());
+
+        let server_queue = queue.clone();
+        let server = tokio::spawn(async move {
+            for _ in 0..3 {
+                let (stream, _) = listener.accept().await.unwrap();
+                handle_connection(stream, &server_queue).await.unwrap();
+            }
+        });
+
+        for (position, text) in ["one", "two", "three"].into_iter().enumerate() {
+            let response = send(&socket_path, &request(text)).await.unwrap();
+            assert_eq!(
+                response,
+                DaemonResponse::Queued {
+                    position,
+                    coalesced: false
+                }
+            );
+        }
+        server.await.unwrap();
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop().await.text(), "one");
+    }
+}
@@ -0,0 +1,389 @@
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::audio::AudioPlayer;
+use crate::config::Config;
+use crate::summarizer::{self, AnthropicClient};
+use crate::tts::{ElevenLabsClient, TtsChain};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Works, but with reduced functionality
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Pass => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        })
+    }
+}
+
+/// One row of the `doctor` report.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Run every check against the effective configuration.
+pub async fn run_checks(config: &Config) -> Vec<Check> {
+    let mut checks = vec![
+        check_config(config),
+        check_cache_dir(&config.cache_dir),
+        check_audio(config),
+        check_tts(config),
+    ];
+    checks.extend(check_eleven_labs(config).await);
+    checks.push(check_summarizer(config).await);
+    checks.push(check_hooks(&claude_settings_path()));
+    checks
+}
+
+/// Whether any check failed outright.
+pub fn has_failures(checks: &[Check]) -> bool {
+    checks.iter().any(|check| check.status == Status::Fail)
+}
+
+pub fn render_table(checks: &[Check]) -> String {
+    let width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for check in checks {
+        out.push_str(&format!(
+            "{:<width$}  {:<4}  {}\n",
+            check.name, check.status, check.detail
+        ));
+    }
+    out
+}
+
+fn check_config(config: &Config) -> Check {
+    let problems = config.problems();
+    if problems.is_empty() {
+        Check::new("config", Status::Pass, "valid")
+    } else {
+        Check::new("config", Status::Fail, problems.join("; "))
+    }
+}
+
+fn check_cache_dir(cache_dir: &Path) -> Check {
+    let probe = cache_dir.join(".doctor_probe");
+    let result = std::fs::create_dir_all(cache_dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::new(
+            "cache dir",
+            Status::Pass,
+            format!("{} is writable", cache_dir.display()),
+        ),
+        Err(e) => Check::new(
+            "cache dir",
+            Status::Fail,
+            format!("{}: {}", cache_dir.display(), e),
+        ),
+    }
+}
+
+fn check_audio(config: &Config) -> Check {
+    let player = AudioPlayer::from_config(config);
+    let names: Vec<&str> = player
+        .backends()
+        .iter()
+        .filter(|backend| backend.is_available())
+        .map(|backend| backend.name())
+        .collect();
+    if names.is_empty() {
+        Check::new(
+            "audio backend",
+            Status::Fail,
+            "no player found; install mpv, ffplay or paplay, or build with native-audio",
+        )
+    } else {
+        Check::new("audio backend", Status::Pass, names.join(", "))
+    }
+}
+
+fn check_tts(config: &Config) -> Check {
+    let chain = TtsChain::from_config(config);
+    let names: Vec<&str> = chain.providers().map(|provider| provider.name()).collect();
+    if names.is_empty() {
+        Check::new(
+            "tts providers",
+            Status::Fail,
+            "none usable; only the macOS say fallback is left",
+        )
+    } else {
+        Check::new("tts providers", Status::Pass, names.join(", "))
+    }
+}
+
+async fn check_eleven_labs(config: &Config) -> Vec<Check> {
+    let Some(api_key) = &config.eleven_labs_api_key else {
+        let status = if config.tts_providers.iter().any(|name| name == "elevenlabs") {
+            Status::Warn
+        } else {
+            Status::Pass
+        };
+        return vec![Check::new(
+            "elevenlabs key",
+            status,
+            "not set; ElevenLabs is skipped",
+        )];
+    };
+
+    let client = ElevenLabsClient::new(
+        api_key.clone(),
+        config.eleven_labs_voice_id.clone(),
+        config.eleven_labs_model_id.clone(),
+    )
+    .with_base_url(&config.eleven_labs_base_url);
+
+    if let Err(e) = client.check_api_key().await {
+        return vec![
+            Check::new("elevenlabs key", Status::Fail, format!("{:#}", e)),
+            Check::new("elevenlabs voice", Status::Warn, "not checked"),
+        ];
+    }
+    let voice = match client.check_voice().await {
+        Ok(()) => Check::new(
+            "elevenlabs voice",
+            Status::Pass,
+            config.eleven_labs_voice_id.clone(),
+        ),
+        Err(e) => Check::new(
+            "elevenlabs voice",
+            Status::Fail,
+            format!("{}: {:#}", config.eleven_labs_voice_id, e),
+        ),
+    };
+    vec![
+        Check::new("elevenlabs key", Status::Pass, "accepted"),
+        voice,
+    ]
+}
+
+async fn check_summarizer(config: &Config) -> Check {
+    match config.summarizer.as_str() {
+        "anthropic" => {}
+        "none" => {
+            return Check::new(
+                "summarizer",
+                Status::Warn,
+                "disabled; transcripts are truncated",
+            )
+        }
+        other => {
+            return match summarizer::from_config(config) {
+                Some(_) => Check::new("summarizer", Status::Pass, other.to_string()),
+                None => Check::new(
+                    "summarizer",
+                    Status::Warn,
+                    format!("{other} is missing an API key or base URL"),
+                ),
+            }
+        }
+    }
+
+    let Some(api_key) = &config.anthropic_api_key else {
+        return Check::new(
+            "anthropic key",
+            Status::Warn,
+            "not set; transcripts are read without summarizing",
+        );
+    };
+    let client = AnthropicClient::new(api_key.clone()).with_base_url(&config.anthropic_base_url);
+    match client.check_api_key().await {
+        Ok(()) => Check::new("anthropic key", Status::Pass, "accepted"),
+        Err(e) => Check::new("anthropic key", Status::Fail, format!("{:#}", e)),
+    }
+}
+
+/// `~/.claude/settings.json`, honouring `CLAUDE_CONFIG_DIR`.
+fn claude_settings_path() -> PathBuf {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".claude")))
+        .unwrap_or_else(|| PathBuf::from(".claude"))
+        .join("settings.json")
+}
+
+fn check_hooks(settings_path: &Path) -> Check {
+    let settings = match std::fs::read_to_string(settings_path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return Check::new(
+                "claude hooks",
+                Status::Fail,
+                format!("{}: {}", settings_path.display(), e),
+            )
+        }
+    };
+    let settings: Value = match serde_json::from_str(&settings) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return Check::new(
+                "claude hooks",
+                Status::Fail,
+                format!("{} is not valid JSON: {}", settings_path.display(), e),
+            )
+        }
+    };
+
+    let missing: Vec<&str> = ["Stop", "Notification"]
+        .into_iter()
+        .filter(|event| !hook_is_wired(&settings, event))
+        .collect();
+    match missing.as_slice() {
+        [] => Check::new("claude hooks", Status::Pass, "Stop and Notification"),
+        ["Notification"] => Check::new(
+            "claude hooks",
+            Status::Warn,
+            "Stop only; permission requests are not announced",
+        ),
+        _ => Check::new(
+            "claude hooks",
+            Status::Fail,
+            format!(
+                "no voice-notifier command for {} in {}",
+                missing.join(" or "),
+                settings_path.display()
+            ),
+        ),
+    }
+}
+
+/// Whether `hooks.<event>[].hooks[].command`, or the flat
+/// `hooks.<event>[].command` shown in the README, runs voice-notifier.
+fn hook_is_wired(settings: &Value, event: &str) -> bool {
+    settings["hooks"][event]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|matcher| {
+            let nested = matcher["hooks"].as_array().into_iter().flatten();
+            std::iter::once(matcher).chain(nested)
+        })
+        .filter_map(|hook| hook["command"].as_str())
+        .any(|command| command.contains("voice-notifier") || command.contains("voice_notifier"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(server_url: &str) -> Config {
+        let mut config: Config = toml::from_str("").unwrap();
+        config.eleven_labs_api_key = Some("el-key".to_string());
+        config.eleven_labs_voice_id = "voice-1".to_string();
+        config.eleven_labs_base_url = server_url.to_string();
+        config.anthropic_api_key = Some("ant-key".to_string());
+        config.anthropic_base_url = server_url.to_string();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_probes_report_rejected_key_and_unknown_voice() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/user")
+            .match_header("xi-api-key", "el-key")
+            .with_status(200)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/v1/voices/voice-1")
+            .with_status(404)
+            .with_body("voice_not_found")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/v1/models?limit=1")
+            .match_header("x-api-key", "ant-key")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let config = config(&server.url());
+        let eleven_labs = check_eleven_labs(&config).await;
+        assert_eq!(eleven_labs[0].status, Status::Pass);
+        assert_eq!(eleven_labs[1].status, Status::Fail);
+        assert!(eleven_labs[1].detail.contains("voice_not_found"));
+        assert_eq!(check_summarizer(&config).await.status, Status::Fail);
+    }
+
+    #[test]
+    fn test_hooks_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        assert_eq!(check_hooks(&path).status, Status::Fail);
+
+        let hook = |command: &str| serde_json::json!([{ "matcher": "", "hooks": [{ "type": "command", "command": command }] }]);
+        let settings = serde_json::json!({
+            "hooks": {
+                "Stop": hook("~/bin/voice-notifier hook"),
+                "Notification": hook("notify-send hi"),
+            }
+        });
+        std::fs::write(&path, settings.to_string()).unwrap();
+        let check = check_hooks(&path);
+        assert_eq!(check.status, Status::Warn);
+
+        let settings = serde_json::json!({
+            "hooks": {
+                "Stop": hook("voice-notifier hook"),
+                "Notification": hook("voice-notifier hook"),
+            }
+        });
+        std::fs::write(&path, settings.to_string()).unwrap();
+        assert_eq!(check_hooks(&path).status, Status::Pass);
+    }
+
+    #[test]
+    fn test_hooks_detection_flat_form() {
+        let settings = serde_json::json!({
+            "hooks": {
+                "Stop": [{ "command": "/path/to/voice-notifier hook" }],
+                "Notification": [{ "command": "notify-send hi" }],
+            }
+        });
+        assert!(hook_is_wired(&settings, "Stop"));
+        assert!(!hook_is_wired(&settings, "Notification"));
+        assert!(!hook_is_wired(&settings, "PreToolUse"));
+    }
+
+    #[test]
+    fn test_render_table_and_failures() {
+        let checks = vec![
+            Check::new("config", Status::Pass, "valid"),
+            Check::new("claude hooks", Status::Fail, "missing"),
+        ];
+        assert_eq!(
+            render_table(&checks),
+            "config        ok    valid\nclaude hooks  FAIL  missing\n"
+        );
+        assert!(has_failures(&checks));
+        assert!(!has_failures(&checks[..1]));
+    }
+}
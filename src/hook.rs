@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Response printed on stdout so the agent is allowed to stop.
+pub const APPROVE_RESPONSE: &str = r#"{"decision": "approve"}"#;
+
+/// JSON payload an agent writes to a hook's stdin.
+///
+/// Claude Code uses snake_case keys while Droid uses camelCase, so every
+/// field accepts both spellings.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HookPayload {
+    #[serde(default, alias = "sessionId")]
+    pub session_id: Option<String>,
+
+    #[serde(default, alias = "transcriptPath")]
+    pub transcript_path: Option<PathBuf>,
+
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    #[serde(default, alias = "hookEventName")]
+    pub hook_event_name: Option<String>,
+
+    #[serde(default)]
+    pub message: Option<String>,
+
+    #[serde(default, alias = "stopHookActive")]
+    pub stop_hook_active: bool,
+}
+
+impl HookPayload {
+    pub fn from_json(input: &str) -> Result<Self> {
+        serde_json::from_str(input).context("Failed to parse hook payload")
+    }
+
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .context("Failed to read hook payload")?;
+        debug!("Raw hook payload: {}", input);
+        Self::from_json(&input)
+    }
+
+    pub fn event_name(&self) -> &str {
+        self.hook_event_name.as_deref().unwrap_or("")
+    }
+
+    /// Transcript path, only if it points at an existing file.
+    pub fn transcript(&self) -> Option<&Path> {
+        self.transcript_path
+            .as_deref()
+            .filter(|path| !path.as_os_str().is_empty() && path.is_file())
+    }
+
+    /// The idle-timeout Notification ("Claude is waiting for your input") is
+    /// already covered by the Stop hook, so it should not be announced.
+    pub fn is_idle_notification(&self) -> bool {
+        if self.event_name() != "Notification" {
+            return false;
+        }
+        match self.message.as_deref().map(str::trim) {
+            None | Some("") => true,
+            Some(message) => message.ends_with("is waiting for your input"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snake_case_payload() {
+        let payload = HookPayload::from_json(
+            r#"{"session_id":"abc","transcript_path":"/tmp/t.jsonl","cwd":"/src/api","hook_event_name":"Stop","stop_hook_active":true}"#,
+        )
+        .unwrap();
+        assert_eq!(payload.session_id.as_deref(), Some("abc"));
+        assert_eq!(payload.transcript_path, Some(PathBuf::from("/tmp/t.jsonl")));
+        assert_eq!(payload.cwd, Some(PathBuf::from("/src/api")));
+        assert_eq!(payload.event_name(), "Stop");
+        assert!(payload.stop_hook_active);
+    }
+
+    #[test]
+    fn test_parse_camel_case_payload() {
+        let payload = HookPayload::from_json(
+            r#"{"sessionId":"abc","transcriptPath":"/tmp/t.jsonl","hookEventName":"Notification","message":"Droid needs your permission to use Bash"}"#,
+        )
+        .unwrap();
+        assert_eq!(payload.transcript_path, Some(PathBuf::from("/tmp/t.jsonl")));
+        assert_eq!(payload.event_name(), "Notification");
+        assert!(!payload.stop_hook_active);
+        assert!(!payload.is_idle_notification());
+    }
+
+    #[test]
+    fn test_message_with_escaped_quotes() {
+        let payload = HookPayload::from_json(
+            r#"{"hook_event_name":"Notification","message":"Claude needs your permission to run \"rm -rf build\""}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            payload.message.as_deref(),
+            Some(r#"Claude needs your permission to run "rm -rf build""#)
+        );
+    }
+
+    #[test]
+    fn test_idle_notification() {
+        for message in [
+            None,
+            Some("Claude is waiting for your input"),
+            Some("Droid is waiting for your input"),
+        ] {
+            let payload = HookPayload {
+                hook_event_name: Some("Notification".to_string()),
+                message: message.map(str::to_string),
+                ..Default::default()
+            };
+            assert!(payload.is_idle_notification(), "{message:?}");
+        }
+    }
+}
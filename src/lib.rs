@@ -1,5 +1,12 @@
-pub mod anthropic;
 pub mod audio;
 pub mod config;
+pub mod daemon;
+pub mod doctor;
+pub mod hook;
+pub mod policy;
+pub mod queue;
+pub mod speaker;
+pub mod state_file;
+pub mod summarizer;
 pub mod transcript;
 pub mod tts;
@@ -1,37 +1,34 @@
-use anyhow::Result;
-use clap::Parser;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::ffi::OsString;
 use std::fs;
-use std::time::{SystemTime, Duration};
-use tracing::{debug, error, info};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
 
-mod anthropic;
-mod audio;
-mod config;
-mod transcript;
-mod tts;
-
-use anthropic::AnthropicClient;
-use audio::AudioPlayer;
-use config::Config;
-use transcript::extract_last_assistant_message;
-use tts::ElevenLabsClient;
-
-// Simple RAII lock guard that removes the lock file when dropped
-struct LockGuard {
-    path: PathBuf,
-}
-
-impl Drop for LockGuard {
-    fn drop(&mut self) {
-        let _ = fs::remove_file(&self.path);
-    }
-}
+use voice_notifier::audio::AudioPlayer;
+use voice_notifier::config::{Config, LoadOptions};
+use voice_notifier::daemon::{self, DaemonResponse, NotificationRequest};
+use voice_notifier::doctor;
+use voice_notifier::hook::{HookPayload, APPROVE_RESPONSE};
+use voice_notifier::policy::{Admission, PolicyGate};
+use voice_notifier::queue::coalesced_text;
+use voice_notifier::speaker::{
+    attention_phrase, fallback_phrases, finished_phrase, outcome_phrase, sweep_temp_files,
+    Playback, Speaker, DEFAULT_MESSAGE, ORPHAN_TEMP_AGE,
+};
+use voice_notifier::summarizer::prompt::{PromptContext, PromptTemplates};
+use voice_notifier::summarizer::{self, truncate_message, SummaryCache, SummaryRequest, Verbosity};
+use voice_notifier::transcript::{read_last_turn, LastTurn, Outcome, TaskDigest};
+use voice_notifier::tts::{AudioCache, TtsChain, Warmed};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, help = "Audio file to play")]
     file: Option<String>,
 
@@ -47,7 +44,7 @@ struct Args {
     #[arg(long, help = "Keep temporary files for debugging")]
     keep_temp: bool,
 
-    #[arg(long, help = "Transcript file path from Claude")]
+    #[arg(long, help = "Transcript file from Claude Code or another agent")]
     transcript: Option<PathBuf>,
 
     #[arg(long, help = "Hook event type (Stop or Notification)")]
@@ -56,8 +53,93 @@ struct Args {
     #[arg(long, help = "Hook message (for Notification events)")]
     hook_message: Option<String>,
 
-    #[arg(long, help = "Agent name for voice notifications", default_value = "Claude Code")]
+    #[arg(
+        long,
+        help = "Agent session ID (used to coalesce queued notifications)"
+    )]
+    session_id: Option<String>,
+
+    #[arg(long, help = "Project directory the agent is working in")]
+    cwd: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Agent name for voice notifications",
+        default_value = "Claude Code"
+    )]
     agent_name: String,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Config file to use instead of ~/.config/voice-notifier/config.toml"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        help = "Override a config option, e.g. --set volume=0.8 (repeatable)"
+    )]
+    overrides: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "LEVEL",
+        help = "Summary length: terse, normal or detailed"
+    )]
+    verbosity: Option<Verbosity>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Read a Stop/Notification hook payload from stdin, approve it and notify in the background
+    Hook,
+    /// Listen on a Unix socket and play notifications one after another
+    Daemon,
+    /// Check keys, audio, cache dir and hook wiring; exits non-zero on failures
+    Doctor,
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Inspect or empty the speech and summary caches
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print the effective configuration and where each value came from
+    Show,
+    /// Print the summary prompt template used for an event
+    Prompt {
+        /// Hook event, e.g. Stop or Notification
+        #[arg(default_value = "Stop")]
+        event: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// Show how many clips and summaries are cached
+    Stats,
+    /// Evict least recently used clips over `audio_cache_max_mb`
+    Prune,
+    /// Delete every cached clip and summary
+    Clear,
+    /// Synthesize the fixed fallback phrases with every TTS provider
+    Warm {
+        /// Agent to warm phrases for (repeatable); defaults to `agent_names`
+        #[arg(long = "agent", value_name = "NAME")]
+        agents: Vec<String>,
+    },
 }
 
 #[tokio::main]
@@ -67,48 +149,73 @@ async fn main() -> Result<()> {
             EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| EnvFilter::new("voice_notifier=info")),
         )
+        // stdout is reserved for the hook decision JSON
+        .with_writer(std::io::stderr)
         .init();
 
     let args = Args::parse();
-    let config = Config::from_env()?;
-    let player = AudioPlayer::new();
+    let loaded = Config::load(&LoadOptions {
+        config_file: args.config.clone(),
+        project_dir: project_search_dir(&args),
+        overrides: config_overrides(&args),
+    })?;
+    if let Some(Command::Config { action }) = &args.command {
+        match action {
+            ConfigCommand::Show => print!("{}", loaded.describe()?),
+            ConfigCommand::Prompt { event } => {
+                print!(
+                    "{}",
+                    PromptTemplates::from_config(&loaded.config).template(event)
+                )
+            }
+        }
+        return Ok(());
+    }
+    let config = loaded.config;
+    for problem in config.problems() {
+        warn!("Config problem: {}", problem);
+    }
 
     // Ensure cache directory exists
     if let Err(e) = config.ensure_cache_dir() {
         error!("Failed to create cache directory: {}", e);
     }
+    match sweep_temp_files(&config.cache_dir, ORPHAN_TEMP_AGE) {
+        Ok(0) => {}
+        Ok(removed) => info!("Removed {} orphaned temp audio files", removed),
+        Err(e) => warn!("Failed to sweep temp audio files: {}", e),
+    }
 
-    // Check for active notification lock
-    let lock_file = config.cache_dir.join("notification.lock");
-    debug!("Checking for lock file at: {:?}", lock_file);
-    if lock_file.exists() {
-        debug!("Lock file exists, checking age");
-        // Check if lock is stale (older than 30 seconds)
-        if let Ok(metadata) = fs::metadata(&lock_file) {
-            if let Ok(modified) = metadata.modified() {
-                if let Ok(elapsed) = SystemTime::now().duration_since(modified) {
-                    if elapsed < Duration::from_secs(30) {
-                        info!("Another notification is in progress (lock age: {:?}), skipping", elapsed);
-                        return Ok(());
-                    } else {
-                        debug!("Removing stale lock file (age: {:?})", elapsed);
-                        let _ = fs::remove_file(&lock_file);
-                    }
-                }
+    match args.command {
+        Some(Command::Hook) => return run_hook(&config, &args),
+        Some(Command::Daemon) => return daemon::run(config).await,
+        Some(Command::Doctor) => {
+            let checks = doctor::run_checks(&config).await;
+            print!("{}", doctor::render_table(&checks));
+            if doctor::has_failures(&checks) {
+                std::process::exit(1);
             }
+            return Ok(());
         }
-    } else {
-        debug!("No lock file found");
+        Some(Command::Cache { action }) => return run_cache_command(&config, action).await,
+        Some(Command::Config { .. }) | None => {}
     }
 
-    // Create lock file
-    if let Err(e) = fs::write(&lock_file, std::process::id().to_string()) {
-        error!("Failed to create lock file: {}", e);
-        // Continue anyway, as this shouldn't block notifications entirely
+    if args.hook_event.as_deref() == Some("Stop") && !config.announce_stop {
+        info!("Stop announcements are disabled for this project");
+        return Ok(());
+    }
+
+    let socket_path = config.daemon_socket_path();
+    let use_daemon = daemon::is_running(&socket_path).await;
+    if use_daemon {
+        debug!("Daemon is running, notifications will be queued");
     }
 
-    // Ensure lock file is removed on exit
-    let _lock_guard = LockGuard { path: lock_file };
+    let speaker = Speaker::new(config.clone())
+        .force_say(args.force_say)
+        .keep_temp(args.keep_temp);
+    let player = speaker.player();
 
     // Check if system is muted before processing text notifications
     if player.is_system_muted().await {
@@ -116,14 +223,25 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    let text = if args.test {
+    let project = config.project_name.clone().or_else(|| {
+        args.cwd
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .and_then(|dir| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+    });
+
+    let (text, outcome) = if args.test {
         info!("Running in test mode");
-        "Claude has finished a task".to_string()
+        (DEFAULT_MESSAGE.to_string(), None)
     } else if let Some(text) = args.text {
-        text
-    } else if args.file.is_some() {
+        (text, None)
+    } else if let Some(file) = args.file {
         // Just play the file, no TTS needed
-        return player.play_audio_file_background(args.file.unwrap()).await;
+        player.play_audio_file_background(file).await?;
+        return Ok(());
     } else if let Some(transcript_path) = args.transcript {
         // Process transcript to get summary
         if let Some(event_type) = &args.hook_event {
@@ -133,85 +251,403 @@ async fn main() -> Result<()> {
                 event_type,
                 args.hook_message.as_deref(),
                 &args.agent_name,
+                project.as_deref(),
             )
             .await
             {
-                Ok(summary) => summary,
+                Ok((summary, outcome)) => (summary, Some(outcome)),
                 Err(e) => {
                     error!("Failed to process transcript: {}", e);
                     match event_type.as_str() {
-                        "Notification" => format!("{} needs your attention", args.agent_name),
-                        _ => format!("{} has finished a task", args.agent_name),
+                        "Notification" => {
+                            (attention_phrase(&args.agent_name), Some(Outcome::Blocked))
+                        }
+                        _ => (finished_phrase(&args.agent_name), None),
                     }
                 }
             }
         } else {
             // Legacy mode without event type
-            match process_transcript(&config, &transcript_path, &args.agent_name).await {
-                Ok(summary) => summary,
+            match process_transcript(
+                &config,
+                &transcript_path,
+                &args.agent_name,
+                project.as_deref(),
+            )
+            .await
+            {
+                Ok((summary, outcome)) => (summary, Some(outcome)),
                 Err(e) => {
                     error!("Failed to process transcript: {}", e);
-                    format!("{} has finished a task", args.agent_name)
+                    (finished_phrase(&args.agent_name), None)
                 }
             }
         }
     } else {
-        format!("{} has finished a task", args.agent_name)
+        (finished_phrase(&args.agent_name), None)
     };
 
-    // Try different methods in order
-    if !args.force_say && config.has_eleven_labs_config() {
-        match generate_and_play_elevenlabs(&config, &player, &text, args.keep_temp).await {
-            Ok(_) => return Ok(()),
-            Err(e) => {
-                error!("ElevenLabs failed: {}", e);
-                info!("Falling back to alternative methods");
+    let event = args.hook_event.as_deref().unwrap_or("Other");
+    let Some(text) =
+        apply_policy(&config, event, text, &args.agent_name, project.as_deref()).await?
+    else {
+        return Ok(());
+    };
+
+    if use_daemon {
+        let request = NotificationRequest {
+            text: text.clone(),
+            event: args.hook_event.clone(),
+            agent_name: args.agent_name.clone(),
+            session_id: args.session_id.clone(),
+            project,
+            outcome,
+        };
+        match daemon::send(&socket_path, &request).await {
+            Ok(DaemonResponse::Queued {
+                position,
+                coalesced,
+            }) => {
+                if coalesced {
+                    info!("Notification merged into a queued one ({} ahead)", position);
+                } else {
+                    info!("Notification queued by daemon ({} ahead)", position);
+                }
+                return Ok(());
+            }
+            Ok(DaemonResponse::Error { message }) => {
+                error!("Daemon rejected notification: {}", message)
+            }
+            Err(e) => error!("Failed to reach daemon: {:#}", e),
+        }
+        info!("Falling back to direct playback");
+    }
+
+    if let Some(outcome) = outcome {
+        if let Some(speaker) = speaker.for_outcome(outcome)? {
+            return speaker.speak(&text, Playback::Background).await;
+        }
+    }
+    speaker.speak(&text, Playback::Background).await
+}
+
+async fn run_cache_command(config: &Config, action: CacheCommand) -> Result<()> {
+    let audio = AudioCache::from_config(config);
+    let summaries = SummaryCache::from_config(config);
+
+    match action {
+        CacheCommand::Stats => {
+            match &audio {
+                Some(audio) => {
+                    let stats = audio.stats()?;
+                    println!(
+                        "Audio cache:   {} clips, {} of {} ({})",
+                        stats.clips,
+                        megabytes(stats.bytes),
+                        megabytes(audio.max_bytes()),
+                        audio.dir().display()
+                    );
+                }
+                None => println!("Audio cache:   disabled"),
+            }
+            match &summaries {
+                Some(summaries) => println!(
+                    "Summary cache: {} summaries ({})",
+                    summaries.count()?,
+                    summaries.path().display()
+                ),
+                None => println!("Summary cache: disabled"),
+            }
+        }
+        CacheCommand::Prune => {
+            let removed = match &audio {
+                Some(audio) => audio.prune()?,
+                None => 0,
+            };
+            println!("Evicted {removed} clips");
+        }
+        CacheCommand::Clear => {
+            let clips = match &audio {
+                Some(audio) => audio.clear()?,
+                None => 0,
+            };
+            let summaries = match summaries {
+                Some(summaries) => summaries.clear()?,
+                None => 0,
+            };
+            println!("Removed {clips} clips and {summaries} summaries");
+        }
+        CacheCommand::Warm { agents } => {
+            let agents = if agents.is_empty() {
+                config.agent_names.clone()
+            } else {
+                agents
+            };
+            let mut phrases = vec![DEFAULT_MESSAGE.to_string()];
+            phrases.extend(agents.iter().flat_map(|agent| fallback_phrases(agent)));
+
+            if TtsChain::from_config(config).is_empty() {
+                anyhow::bail!("No TTS provider is available");
+            }
+            // Voices and providers set under `[outcomes.<outcome>]` are
+            // cached too, so failures and questions also play offline
+            let mut configs = vec![("default", config.clone())];
+            for outcome in Outcome::ALL {
+                if config.outcomes.contains_key(outcome.as_str()) {
+                    configs.push((outcome.as_str(), config.for_outcome(outcome)?));
+                }
+            }
+
+            let mut failed = false;
+            for (label, config) in &configs {
+                let chain = TtsChain::from_config(config);
+                if chain.is_empty() {
+                    warn!("No TTS provider is available for the {} outcome", label);
+                    continue;
+                }
+                let playable = AudioPlayer::from_config(config).supported_formats();
+                for phrase in &phrases {
+                    for (provider, result) in chain.warm(phrase, &playable).await? {
+                        let status = match result {
+                            Ok(Warmed::AlreadyCached) => "cached".to_string(),
+                            Ok(Warmed::Synthesized) => "synthesized".to_string(),
+                            Err(e) => {
+                                failed = true;
+                                format!("FAILED ({e:#})")
+                            }
+                        };
+                        println!("{label:<9} {provider:<12} {phrase:<40} {status}");
+                    }
+                }
+            }
+            if failed {
+                anyhow::bail!("Some phrases could not be synthesized");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// `--set` assignments, plus shorthand flags such as `--verbosity`.
+fn config_overrides(args: &Args) -> Vec<String> {
+    let mut overrides = args.overrides.clone();
+    if let Some(verbosity) = args.verbosity {
+        overrides.push(format!("verbosity={verbosity}"));
+    }
+    overrides
+}
+
+/// Where to look for `.voice-notifier.toml`: the agent's working directory,
+/// else the transcript's directory, else our own working directory.
+fn project_search_dir(args: &Args) -> Option<PathBuf> {
+    args.cwd
+        .clone()
+        .or_else(|| {
+            args.transcript
+                .as_deref()
+                .and_then(|path| path.parent())
+                .map(PathBuf::from)
+        })
+        .or_else(|| std::env::current_dir().ok())
+}
+
+/// Run `text` through the cooldown and rate limit policy. Returns the text
+/// to speak, which may now stand for several merged notifications, or
+/// `None` when this process should stay quiet.
+async fn apply_policy(
+    config: &Config,
+    event: &str,
+    text: String,
+    agent_name: &str,
+    project: Option<&str>,
+) -> Result<Option<String>> {
+    let gate = PolicyGate::from_config(config);
+    loop {
+        match gate.admit(event, &text)? {
+            Admission::Play => return Ok(Some(text)),
+            Admission::Drop => {
+                info!("Dropping notification inside the cooldown window");
+                return Ok(None);
+            }
+            Admission::Merged => {
+                info!("Notification merged into a pending one");
+                return Ok(None);
+            }
+            Admission::Defer(wait) => {
+                info!("Deferring notification for {:?}", wait);
+                tokio::time::sleep(wait).await;
+            }
+            Admission::LeadMerge(wait) => {
+                info!("Collecting notifications for {:?} before speaking", wait);
+                tokio::time::sleep(wait).await;
+                let Some(merged) = gate.finish_merge(event)? else {
+                    return Ok(Some(text));
+                };
+                return Ok(Some(if merged.count == 1 {
+                    merged.last_text
+                } else if event == "Stop" {
+                    coalesced_text(agent_name, merged.count, project)
+                } else {
+                    attention_phrase(agent_name)
+                }));
             }
         }
     }
+}
 
-    // Try cached audio if it's the default message
-    if text == "Claude has finished a task" {
-        let cache_file = config.cache_dir.join("default.mp3");
-        if cache_file.exists() {
-            info!("Using cached audio file");
-            match player.play_audio_file_background(&cache_file).await {
-                Ok(_) => return Ok(()),
-                Err(e) => error!("Failed to play cached audio: {}", e),
+/// Handle a hook invocation: the agent blocks until we answer, so the
+/// notification itself runs in a detached child process.
+fn run_hook(config: &Config, args: &Args) -> Result<()> {
+    match HookPayload::from_reader(std::io::stdin().lock()) {
+        Ok(payload) => {
+            if let Err(e) = dispatch_hook(config, &payload, args) {
+                error!("Failed to dispatch hook notification: {:#}", e);
             }
         }
+        Err(e) => error!("{:#}", e),
     }
 
-    // Final fallback: mac say
-    info!("Using mac say as final fallback");
-    player.say_text_background(&text).await?;
+    // Always approve, even if we could not notify
+    println!("{APPROVE_RESPONSE}");
+    Ok(())
+}
+
+fn dispatch_hook(config: &Config, payload: &HookPayload, args: &Args) -> Result<()> {
+    let agent_name = args.agent_name.as_str();
+    let event = payload.event_name();
+    info!(
+        "Hook triggered: {} (session: {:?}, cwd: {:?}, stop_hook_active: {})",
+        event, payload.session_id, payload.cwd, payload.stop_hook_active
+    );
+
+    if !matches!(event, "Stop" | "Notification") {
+        info!("Unknown hook event: {}", event);
+        return Ok(());
+    }
+
+    if payload.is_idle_notification() {
+        info!("Skipping idle timeout notification (already notified by Stop hook)");
+        return Ok(());
+    }
+
+    // The event is passed even with fixed text so the daemon can prioritize it
+    let mut notify_args: Vec<OsString> = vec![
+        "--agent-name".into(),
+        agent_name.into(),
+        "--hook-event".into(),
+        event.into(),
+    ];
+    if let Some(config_file) = &args.config {
+        notify_args.extend(["--config".into(), config_file.into()]);
+    }
+    for assignment in config_overrides(args) {
+        notify_args.extend(["--set".into(), assignment.into()]);
+    }
+    if let Some(session_id) = &payload.session_id {
+        notify_args.extend(["--session-id".into(), session_id.into()]);
+    }
+    if let Some(cwd) = &payload.cwd {
+        notify_args.extend(["--cwd".into(), cwd.into()]);
+    }
+    if let Some(transcript) = payload.transcript() {
+        notify_args.extend(["--transcript".into(), transcript.into()]);
+        if let Some(message) = &payload.message {
+            notify_args.extend(["--hook-message".into(), message.into()]);
+        }
+    } else {
+        debug!("No transcript available, using a fixed message");
+        let text = match event {
+            "Notification" => attention_phrase(agent_name),
+            _ => finished_phrase(agent_name),
+        };
+        notify_args.extend(["-s".into(), text.into()]);
+    }
+
+    spawn_detached(config, &notify_args)
+}
+
+fn spawn_detached(config: &Config, notify_args: &[OsString]) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate voice-notifier binary")?;
+    let log_path = config.hook_log_path();
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create hook log directory")?;
+    }
+    let log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("Failed to open hook log: {}", log_path.display()))?;
+
+    let mut command = std::process::Command::new(exe);
+    command
+        .args(notify_args)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+
+    // Leave the agent's process group so the notification outlives the hook
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
 
+    let child = command
+        .spawn()
+        .context("Failed to spawn notification process")?;
+    debug!("Spawned notification process {}", child.id());
     Ok(())
 }
 
-async fn process_transcript(config: &Config, transcript_path: &PathBuf, agent_name: &str) -> Result<String> {
+async fn process_transcript(
+    config: &Config,
+    transcript_path: &PathBuf,
+    agent_name: &str,
+    project: Option<&str>,
+) -> Result<(String, Outcome)> {
     info!("Processing transcript from: {:?}", transcript_path);
 
-    // Extract the last assistant message
-    let last_message = extract_last_assistant_message(transcript_path)?;
-
-    // If we have an Anthropic API key, summarize the message
-    if let Some(api_key) = &config.anthropic_api_key {
-        let client = AnthropicClient::new(api_key.clone());
-        match client.summarize(&last_message, agent_name).await {
+    let turn = read_last_turn(transcript_path)?;
+    if turn.is_empty() {
+        anyhow::bail!("No assistant message found in transcript");
+    }
+    let input = turn.summary_input();
+    let digest = TaskDigest::from_turn(&turn);
+    let outcome = Outcome::classify(&turn, &digest);
+
+    if let Some(summarizer) = summarizer::from_config(config) {
+        let context = PromptContext {
+            agent_name,
+            event: "Stop",
+            project,
+            outcome: Some(outcome),
+            classify: config.classify_outcome,
+            ..Default::default()
+        };
+        let system_prompt = render_prompt(config, &digest, context);
+        match summarizer
+            .summarize(&SummaryRequest::new(&input, "Stop", &system_prompt))
+            .await
+        {
             Ok(summary) => {
                 info!("Successfully generated summary");
-                Ok(summary)
+                Ok(untag_summary(&summary, outcome))
             }
             Err(e) => {
-                error!("Failed to summarize with Anthropic: {}", e);
+                error!("Failed to summarize with {}: {}", summarizer.name(), e);
                 // Fallback to a simple truncation
-                Ok(truncate_message(&last_message))
+                let summary = fallback_summary(&turn, &digest, outcome, agent_name, config);
+                Ok((summary, outcome))
             }
         }
     } else {
-        info!("No Anthropic API key configured, using simple truncation");
-        Ok(truncate_message(&last_message))
+        info!("No summarizer configured, using simple truncation");
+        let summary = fallback_summary(&turn, &digest, outcome, agent_name, config);
+        Ok((summary, outcome))
     }
 }
 
@@ -221,127 +657,112 @@ async fn process_transcript_with_context(
     event_type: &str,
     message: Option<&str>,
     agent_name: &str,
-) -> Result<String> {
+    project: Option<&str>,
+) -> Result<(String, Outcome)> {
     info!(
         "Processing transcript from: {:?} for event: {}",
         transcript_path, event_type
     );
 
-    // Extract the last assistant message
-    let last_message = extract_last_assistant_message(transcript_path)?;
+    let turn = read_last_turn(transcript_path)?;
+    if turn.is_empty() {
+        anyhow::bail!("No assistant message found in transcript");
+    }
+    let input = turn.summary_input();
+    let digest = TaskDigest::from_turn(&turn);
+    // A notification means the agent is waiting on the user
+    let outcome = match event_type {
+        "Notification" => Outcome::Blocked,
+        _ => Outcome::classify(&turn, &digest),
+    };
 
-    // If we have an Anthropic API key, summarize the message with context
-    if let Some(api_key) = &config.anthropic_api_key {
-        let client = AnthropicClient::new(api_key.clone());
-        match client
-            .summarize_with_context(&last_message, event_type, message, agent_name)
-            .await
-        {
+    if let Some(summarizer) = summarizer::from_config(config) {
+        let context = PromptContext {
+            agent_name,
+            event: event_type,
+            project,
+            message,
+            outcome: Some(outcome),
+            classify: config.classify_outcome,
+            ..Default::default()
+        };
+        let system_prompt = render_prompt(config, &digest, context);
+        let request = SummaryRequest::new(&input, event_type, &system_prompt);
+        match summarizer.summarize(&request).await {
             Ok(summary) => {
                 info!("Successfully generated summary");
-                Ok(summary)
+                Ok(untag_summary(&summary, outcome))
             }
             Err(e) => {
-                error!("Failed to summarize with Anthropic: {}", e);
+                error!("Failed to summarize with {}: {}", summarizer.name(), e);
                 // Fallback based on event type
                 match event_type {
-                    "Notification" => Ok(format!("{} needs your attention", agent_name)),
-                    _ => Ok(truncate_message(&last_message)),
+                    "Notification" => Ok((attention_phrase(agent_name), outcome)),
+                    _ => {
+                        let summary = fallback_summary(&turn, &digest, outcome, agent_name, config);
+                        Ok((summary, outcome))
+                    }
                 }
             }
         }
     } else {
-        info!("No Anthropic API key configured, using simple message");
+        info!("No summarizer configured, using simple message");
         match event_type {
-            "Notification" => Ok(format!("{} needs your attention", agent_name)),
-            _ => Ok(truncate_message(&last_message)),
+            "Notification" => Ok((attention_phrase(agent_name), outcome)),
+            _ => {
+                let summary = fallback_summary(&turn, &digest, outcome, agent_name, config);
+                Ok((summary, outcome))
+            }
         }
     }
 }
 
-fn truncate_message(message: &str) -> String {
-    // Simple fallback: take first sentence or first 100 chars
-    let trimmed = message.trim();
-
-    // Try to find first sentence
-    if let Some(end) = trimmed.find(['.', '!', '?']) {
-        let sentence = &trimmed[..=end];
-        if sentence.len() <= 150 {
-            return sentence.to_string();
-        }
-    }
-
-    // Otherwise, truncate at word boundary
-    if trimmed.len() <= 100 {
-        trimmed.to_string()
-    } else {
-        let truncated = &trimmed[..100];
-        if let Some(last_space) = truncated.rfind(' ') {
-            format!("{}...", &truncated[..last_space])
-        } else {
-            format!("{truncated}...")
-        }
+/// Remove the outcome tag the summarizer was asked for, letting it override
+/// the heuristic outcome.
+fn untag_summary(summary: &str, outcome: Outcome) -> (String, Outcome) {
+    let (tagged, text) = Outcome::strip_tag(summary);
+    if let Some(tagged) = tagged {
+        debug!(
+            "Summarizer tagged the task as {} (heuristic: {})",
+            tagged, outcome
+        );
     }
+    (text.trim().to_string(), tagged.unwrap_or(outcome))
 }
 
-async fn generate_and_play_elevenlabs(
+/// Spoken when no summary is available: what the digest shows was done,
+/// else the turn's final response cut to the configured verbosity.
+fn fallback_summary(
+    turn: &LastTurn,
+    digest: &TaskDigest,
+    outcome: Outcome,
+    agent_name: &str,
     config: &Config,
-    player: &AudioPlayer,
-    text: &str,
-    keep_temp: bool,
-) -> Result<()> {
-    let api_key = config
-        .eleven_labs_api_key
-        .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("ElevenLabs API key not configured"))?;
-
-    let client = ElevenLabsClient::new(
-        api_key.clone(),
-        config.eleven_labs_voice_id.clone(),
-        config.eleven_labs_model_id.clone(),
-    );
-
-    let temp_path = if keep_temp {
-        let debug_path = std::env::current_dir()?.join("debug_audio.mp3");
-        info!("Saving debug audio to: {}", debug_path.display());
-        client.generate_speech(text, &debug_path).await?;
-        match player.play_audio_file_background(&debug_path).await {
-            Ok(_) => {
-                info!("Audio playing in background, debug file saved at: {}", debug_path.display());
-            }
-            Err(e) => {
-                error!("Failed to play audio: {}", e);
-                info!("Debug file saved at: {}", debug_path.display());
-                return Err(e);
-            }
-        }
-        debug_path
-    } else {
-        // Use cache directory for temp files to avoid permission issues
-        config.ensure_cache_dir()?;
-        let temp_path = config
-            .cache_dir
-            .join(format!("temp_voice_notifier_{}.mp3", std::process::id()));
-
-        client.generate_speech(text, &temp_path).await?;
-        
-        // Play audio in background - don't wait for completion
-        player.play_audio_file_background(&temp_path).await?;
-        
-        // Don't clean up temp file immediately since audio is playing in background
-        // The OS will clean it up eventually from the temp directory
-        temp_path
-    };
-
-    // If this is the default message, cache it
-    if text == "Claude has finished a task" {
-        let cache_file = config.cache_dir.join("default.mp3");
-        if let Err(e) = tokio::fs::copy(temp_path, &cache_file).await {
-            error!("Failed to cache audio file: {}", e);
-        } else {
-            info!("Cached default audio for future use");
+) -> String {
+    let final_text = turn
+        .final_text
+        .as_deref()
+        .map(|text| truncate_message(text, config.verbosity));
+    match (digest.describe(agent_name), final_text) {
+        (Some(description), Some(text)) if config.verbosity == Verbosity::Detailed => {
+            format!("{description}. {text}")
         }
+        (Some(description), _) => description,
+        (None, Some(text)) => text,
+        (None, None) => outcome_phrase(agent_name, outcome),
     }
+}
 
-    Ok(())
+/// Render the event's prompt template, adding duration, changed files and
+/// the digest of the transcript's last turn.
+fn render_prompt(config: &Config, digest: &TaskDigest, context: PromptContext) -> String {
+    let files_changed = digest.files_changed();
+    let facts = digest.facts();
+    PromptTemplates::from_config(config).render(&PromptContext {
+        verbosity: config.verbosity,
+        duration: digest.elapsed,
+        files_changed: &files_changed,
+        digest: &facts,
+        ..context
+    })
 }
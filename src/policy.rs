@@ -0,0 +1,363 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::Config;
+use crate::state_file::{self, now_ms};
+
+/// What happens to a notification arriving inside a cooldown window or
+/// over the per-minute limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BurstAction {
+    /// Skip it
+    Drop,
+    /// Wait until the window ends, then play it
+    Defer,
+    /// Fold it into a single notification played when the window ends
+    Merge,
+}
+
+impl FromStr for BurstAction {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "drop" => Ok(BurstAction::Drop),
+            "defer" => Ok(BurstAction::Defer),
+            "merge" => Ok(BurstAction::Merge),
+            other => Err(anyhow::anyhow!(
+                "Unknown burst action '{}' (expected drop, defer or merge)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RatePolicy {
+    /// Minimum gap between two notifications of the same event type
+    pub default_cooldown: Duration,
+    /// Per-event overrides of `default_cooldown`, keyed by hook event name
+    pub event_cooldowns: HashMap<String, Duration>,
+    /// 0 disables the limit
+    pub max_per_minute: u32,
+    pub action: BurstAction,
+}
+
+impl RatePolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            default_cooldown: Duration::from_secs(config.cooldown_secs),
+            event_cooldowns: config
+                .event_cooldown_secs
+                .iter()
+                .map(|(event, secs)| (event.clone(), Duration::from_secs(*secs)))
+                .collect(),
+            max_per_minute: config.max_per_minute,
+            action: config.burst_action,
+        }
+    }
+
+    pub fn cooldown_for(&self, event: &str) -> Duration {
+        self.event_cooldowns
+            .get(event)
+            .copied()
+            .unwrap_or(self.default_cooldown)
+    }
+}
+
+/// The gate's verdict for one notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    /// Play now
+    Play,
+    /// Skip it
+    Drop,
+    /// Ask again after waiting
+    Defer(Duration),
+    /// Another process will speak it as part of a merged notification
+    Merged,
+    /// Wait, then call [`PolicyGate::finish_merge`] and speak the result
+    LeadMerge(Duration),
+}
+
+/// Notifications folded together by [`BurstAction::Merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedNotifications {
+    pub count: usize,
+    /// Text of the most recent notification
+    pub last_text: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PolicyState {
+    #[serde(default)]
+    played: Vec<PlayedEntry>,
+    #[serde(default)]
+    merges: Vec<PendingMerge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PlayedEntry {
+    event: String,
+    at_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingMerge {
+    event: String,
+    until_ms: u64,
+    count: usize,
+    last_text: String,
+}
+
+const MINUTE_MS: u64 = 60_000;
+
+/// Applies a [`RatePolicy`] using state persisted in the cache directory,
+/// so separate short-lived processes honour the same windows.
+pub struct PolicyGate {
+    state_path: PathBuf,
+    policy: RatePolicy,
+}
+
+impl PolicyGate {
+    pub fn new(state_path: PathBuf, policy: RatePolicy) -> Self {
+        Self { state_path, policy }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.cache_dir.join("policy_state.json"),
+            RatePolicy::from_config(config),
+        )
+    }
+
+    pub fn admit(&self, event: &str, text: &str) -> Result<Admission> {
+        self.admit_at(event, text, now_ms())
+    }
+
+    pub fn admit_at(&self, event: &str, text: &str, now: u64) -> Result<Admission> {
+        self.with_state(|state| {
+            self.prune(state, now);
+
+            let Some(window_end) = self.window_end(state, event, now) else {
+                state.played.push(PlayedEntry {
+                    event: event.to_string(),
+                    at_ms: now,
+                });
+                return Admission::Play;
+            };
+            let wait = Duration::from_millis(window_end - now);
+            info!(
+                "Notification inside rate limit window for {:?}, action: {:?}",
+                wait, self.policy.action
+            );
+
+            match self.policy.action {
+                BurstAction::Drop => Admission::Drop,
+                BurstAction::Defer => Admission::Defer(wait),
+                BurstAction::Merge => {
+                    if let Some(merge) = state.merges.iter_mut().find(|m| m.event == event) {
+                        merge.count += 1;
+                        merge.last_text = text.to_string();
+                        Admission::Merged
+                    } else {
+                        state.merges.push(PendingMerge {
+                            event: event.to_string(),
+                            until_ms: window_end,
+                            count: 1,
+                            last_text: text.to_string(),
+                        });
+                        Admission::LeadMerge(wait)
+                    }
+                }
+            }
+        })
+    }
+
+    /// Collect the notifications merged while the leader waited and record
+    /// the merged notification as played.
+    pub fn finish_merge(&self, event: &str) -> Result<Option<MergedNotifications>> {
+        self.finish_merge_at(event, now_ms())
+    }
+
+    pub fn finish_merge_at(&self, event: &str, now: u64) -> Result<Option<MergedNotifications>> {
+        self.with_state(|state| {
+            let index = state.merges.iter().position(|m| m.event == event)?;
+            let merge = state.merges.remove(index);
+            state.played.push(PlayedEntry {
+                event: event.to_string(),
+                at_ms: now,
+            });
+            Some(MergedNotifications {
+                count: merge.count,
+                last_text: merge.last_text,
+            })
+        })
+    }
+
+    /// End of the window blocking `event` right now, if any.
+    fn window_end(&self, state: &PolicyState, event: &str, now: u64) -> Option<u64> {
+        let mut end = None;
+
+        let cooldown = self.policy.cooldown_for(event).as_millis() as u64;
+        if let Some(last) = state
+            .played
+            .iter()
+            .filter(|entry| entry.event == event)
+            .map(|entry| entry.at_ms)
+            .max()
+        {
+            if now < last + cooldown {
+                end = Some(last + cooldown);
+            }
+        }
+
+        let max = self.policy.max_per_minute as usize;
+        if max > 0 {
+            let mut recent: Vec<u64> = state
+                .played
+                .iter()
+                .map(|entry| entry.at_ms)
+                .filter(|at| now < at + MINUTE_MS)
+                .collect();
+            if recent.len() >= max {
+                // A slot frees up when enough of the oldest plays age out
+                recent.sort_unstable();
+                let frees_at = recent[recent.len() - max] + MINUTE_MS;
+                end = Some(end.map_or(frees_at, |end: u64| end.max(frees_at)));
+            }
+        }
+
+        // Merges still collecting keep the window open
+        if let Some(merge) = state.merges.iter().find(|m| m.event == event) {
+            if now < merge.until_ms {
+                end = Some(end.map_or(merge.until_ms, |end: u64| end.max(merge.until_ms)));
+            }
+        }
+
+        end
+    }
+
+    fn prune(&self, state: &mut PolicyState, now: u64) {
+        let longest = self
+            .policy
+            .event_cooldowns
+            .values()
+            .copied()
+            .chain([self.policy.default_cooldown])
+            .max()
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let keep = longest.max(MINUTE_MS);
+        state.played.retain(|entry| now < entry.at_ms + keep);
+        // Drop merges whose leader died without finishing them
+        state
+            .merges
+            .retain(|merge| now < merge.until_ms + MINUTE_MS);
+    }
+
+    /// Run `f` on the state while holding an exclusive file lock.
+    fn with_state<T>(&self, f: impl FnOnce(&mut PolicyState) -> T) -> Result<T> {
+        state_file::with_locked(&self.state_path, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(dir: &tempfile::TempDir, action: BurstAction, max_per_minute: u32) -> PolicyGate {
+        PolicyGate::new(
+            dir.path().join("policy_state.json"),
+            RatePolicy {
+                default_cooldown: Duration::from_secs(10),
+                event_cooldowns: HashMap::from([("Notification".to_string(), Duration::ZERO)]),
+                max_per_minute,
+                action,
+            },
+        )
+    }
+
+    #[test]
+    fn test_cooldown_per_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate(&dir, BurstAction::Drop, 0);
+        assert_eq!(gate.admit_at("Stop", "a", 0).unwrap(), Admission::Play);
+        assert_eq!(gate.admit_at("Stop", "b", 5_000).unwrap(), Admission::Drop);
+        assert_eq!(
+            gate.admit_at("Notification", "c", 5_000).unwrap(),
+            Admission::Play
+        );
+        assert_eq!(gate.admit_at("Stop", "d", 10_000).unwrap(), Admission::Play);
+    }
+
+    #[test]
+    fn test_defer_waits_for_window_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate(&dir, BurstAction::Defer, 0);
+        gate.admit_at("Stop", "a", 1_000).unwrap();
+        assert_eq!(
+            gate.admit_at("Stop", "b", 4_000).unwrap(),
+            Admission::Defer(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn test_max_per_minute() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate(&dir, BurstAction::Defer, 2);
+        assert_eq!(
+            gate.admit_at("Notification", "a", 0).unwrap(),
+            Admission::Play
+        );
+        assert_eq!(
+            gate.admit_at("Notification", "b", 1_000).unwrap(),
+            Admission::Play
+        );
+        assert_eq!(
+            gate.admit_at("Notification", "c", 2_000).unwrap(),
+            Admission::Defer(Duration::from_secs(58))
+        );
+        assert_eq!(
+            gate.admit_at("Notification", "d", 60_000).unwrap(),
+            Admission::Play
+        );
+    }
+
+    #[test]
+    fn test_merge_across_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate(&dir, BurstAction::Merge, 0);
+        assert_eq!(gate.admit_at("Stop", "a", 0).unwrap(), Admission::Play);
+        assert_eq!(
+            gate.admit_at("Stop", "b", 2_000).unwrap(),
+            Admission::LeadMerge(Duration::from_secs(8))
+        );
+        // A second gate stands in for another short-lived process
+        let other = super::tests::gate(&dir, BurstAction::Merge, 0);
+        assert_eq!(
+            other.admit_at("Stop", "c", 3_000).unwrap(),
+            Admission::Merged
+        );
+
+        let merged = gate.finish_merge_at("Stop", 10_000).unwrap().unwrap();
+        assert_eq!(merged.count, 2);
+        assert_eq!(merged.last_text, "c");
+        assert_eq!(
+            gate.admit_at("Stop", "d", 12_000).unwrap(),
+            Admission::LeadMerge(Duration::from_secs(8))
+        );
+    }
+
+    #[test]
+    fn test_burst_action_from_str() {
+        assert_eq!("Merge".parse::<BurstAction>().unwrap(), BurstAction::Merge);
+        assert!("ignore".parse::<BurstAction>().is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use crate::daemon::NotificationRequest;
+
+/// Playback order: higher priorities jump ahead of lower ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Normal,
+    /// Permission requests and other `Notification` events
+    High,
+}
+
+impl Priority {
+    pub fn for_event(event: Option<&str>) -> Self {
+        match event {
+            Some("Notification") => Priority::High,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// A pending notification, possibly standing in for several coalesced ones.
+#[derive(Debug, Clone)]
+pub struct QueuedNotification {
+    pub request: NotificationRequest,
+    pub priority: Priority,
+    /// How many Stop summaries were merged into this entry
+    pub count: usize,
+}
+
+impl QueuedNotification {
+    fn new(request: NotificationRequest) -> Self {
+        Self {
+            priority: Priority::for_event(request.event.as_deref()),
+            request,
+            count: 1,
+        }
+    }
+
+    /// Stop summaries from the same session collapse into one entry.
+    fn can_absorb(&self, request: &NotificationRequest) -> bool {
+        self.priority == Priority::Normal
+            && self.request.event.as_deref() == Some("Stop")
+            && request.event.as_deref() == Some("Stop")
+            && self.request.session_id.is_some()
+            && self.request.session_id == request.session_id
+    }
+
+    /// The sentence to speak.
+    pub fn text(&self) -> String {
+        if self.count == 1 {
+            return self.request.text.clone();
+        }
+        coalesced_text(
+            &self.request.agent_name,
+            self.count,
+            self.request.project.as_deref(),
+        )
+    }
+}
+
+/// Sentence replacing several Stop summaries, e.g.
+/// "Claude Code finished three tasks in the api repo".
+pub fn coalesced_text(agent_name: &str, count: usize, project: Option<&str>) -> String {
+    let tasks = format!("{} tasks", number_word(count));
+    match project {
+        Some(project) => format!("{} finished {} in the {} repo", agent_name, tasks, project),
+        None => format!("{} finished {}", agent_name, tasks),
+    }
+}
+
+fn number_word(count: usize) -> String {
+    const WORDS: [&str; 11] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    WORDS
+        .get(count)
+        .map(|word| word.to_string())
+        .unwrap_or_else(|| count.to_string())
+}
+
+/// Outcome of adding a notification to the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enqueued {
+    /// Notifications that will play before this one
+    pub position: usize,
+    /// Whether it was merged into an already queued notification
+    pub coalesced: bool,
+}
+
+/// Priority queue of pending notifications shared by the socket and
+/// playback tasks. Entries of equal priority play in arrival order.
+#[derive(Default)]
+pub struct NotificationQueue {
+    pending: Mutex<VecDeque<QueuedNotification>>,
+    notify: Notify,
+}
+
+impl NotificationQueue {
+    pub fn push(&self, request: NotificationRequest) -> Enqueued {
+        let mut pending = self.pending.lock().unwrap();
+
+        if let Some(position) = pending
+            .iter()
+            .position(|queued| queued.can_absorb(&request))
+        {
+            let queued = &mut pending[position];
+            queued.count += 1;
+            queued.request.text = request.text;
+            queued.request.outcome = request.outcome;
+            return Enqueued {
+                position,
+                coalesced: true,
+            };
+        }
+
+        let queued = QueuedNotification::new(request);
+        let position = pending
+            .iter()
+            .position(|other| other.priority < queued.priority)
+            .unwrap_or(pending.len());
+        pending.insert(position, queued);
+        self.notify.notify_one();
+        Enqueued {
+            position,
+            coalesced: false,
+        }
+    }
+
+    pub fn try_pop(&self) -> Option<QueuedNotification> {
+        self.pending.lock().unwrap().pop_front()
+    }
+
+    /// Wait until a notification is available.
+    pub async fn pop(&self) -> QueuedNotification {
+        loop {
+            if let Some(queued) = self.try_pop() {
+                return queued;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(text: &str, event: &str, session: Option<&str>) -> NotificationRequest {
+        NotificationRequest {
+            text: text.to_string(),
+            event: Some(event.to_string()),
+            agent_name: "Claude Code".to_string(),
+            session_id: session.map(str::to_string),
+            project: Some("api".to_string()),
+            outcome: None,
+        }
+    }
+
+    #[test]
+    fn test_notifications_jump_ahead_of_stops() {
+        let queue = NotificationQueue::default();
+        queue.push(request("stop a", "Stop", Some("a")));
+        queue.push(request("stop b", "Stop", Some("b")));
+        let enqueued = queue.push(request("permission", "Notification", Some("c")));
+        assert_eq!(enqueued.position, 0);
+        queue.push(request("permission 2", "Notification", Some("d")));
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.try_pop())
+            .map(|queued| queued.text())
+            .collect();
+        assert_eq!(order, ["permission", "permission 2", "stop a", "stop b"]);
+    }
+
+    #[test]
+    fn test_stops_from_same_session_coalesce() {
+        let queue = NotificationQueue::default();
+        queue.push(request("first", "Stop", Some("a")));
+        queue.push(request("other session", "Stop", Some("b")));
+        assert!(queue.push(request("second", "Stop", Some("a"))).coalesced);
+        assert!(queue.push(request("third", "Stop", Some("a"))).coalesced);
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(
+            queue.try_pop().unwrap().text(),
+            "Claude Code finished three tasks in the api repo"
+        );
+        assert_eq!(queue.try_pop().unwrap().text(), "other session");
+    }
+
+    #[test]
+    fn test_without_session_nothing_coalesces() {
+        let queue = NotificationQueue::default();
+        queue.push(request("first", "Stop", None));
+        assert!(!queue.push(request("second", "Stop", None)).coalesced);
+        assert_eq!(queue.len(), 2);
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn};
+
+use crate::audio::AudioPlayer;
+use crate::config::Config;
+use crate::transcript::Outcome;
+use crate::tts::TtsChain;
+
+/// Phrase spoken by `--test`.
+pub const DEFAULT_MESSAGE: &str = "Claude has finished a task";
+
+/// Spoken when a finished task has no summary.
+pub fn finished_phrase(agent_name: &str) -> String {
+    format!("{agent_name} has finished a task")
+}
+
+/// Spoken for a notification without a summary.
+pub fn attention_phrase(agent_name: &str) -> String {
+    format!("{agent_name} needs your attention")
+}
+
+/// Spoken for a finished task without a summary, worded for its outcome.
+pub fn outcome_phrase(agent_name: &str, outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Success => finished_phrase(agent_name),
+        Outcome::Failure => format!("{agent_name} ran into a problem"),
+        Outcome::Blocked => attention_phrase(agent_name),
+        Outcome::Question => format!("{agent_name} has a question for you"),
+    }
+}
+
+/// Every fixed phrase that can be spoken for `agent_name`, synthesized ahead
+/// of time by `cache warm` so they play while TTS is unreachable.
+pub fn fallback_phrases(agent_name: &str) -> Vec<String> {
+    Outcome::ALL
+        .iter()
+        .map(|outcome| outcome_phrase(agent_name, *outcome))
+        .collect()
+}
+
+/// Prefix of the clips written to the cache dir for playback.
+const TEMP_PREFIX: &str = "temp_voice_notifier_";
+
+/// Temp clips older than this were left behind by a killed or crashed process.
+pub const ORPHAN_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Longer text is synthesized and played a few sentences at a time, so
+/// detailed summaries start speaking sooner and stay under provider limits.
+const CHUNK_CHARS: usize = 300;
+
+/// Whether `speak` returns as soon as audio starts or once it has finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    Background,
+    UntilFinished,
+}
+
+/// Turns text into audible speech: cached or freshly synthesized audio from
+/// the TTS providers, then `say`.
+pub struct Speaker {
+    config: Config,
+    player: AudioPlayer,
+    tts: TtsChain,
+    force_say: bool,
+    keep_temp: bool,
+}
+
+impl Speaker {
+    pub fn new(config: Config) -> Self {
+        Self {
+            player: AudioPlayer::from_config(&config),
+            tts: TtsChain::from_config(&config),
+            config,
+            force_say: false,
+            keep_temp: false,
+        }
+    }
+
+    /// Skip TTS providers and go straight to `say`.
+    pub fn force_say(mut self, force_say: bool) -> Self {
+        self.force_say = force_say;
+        self
+    }
+
+    /// Save synthesized audio as `debug_audio.*` in the working directory.
+    pub fn keep_temp(mut self, keep_temp: bool) -> Self {
+        self.keep_temp = keep_temp;
+        self
+    }
+
+    pub fn player(&self) -> &AudioPlayer {
+        &self.player
+    }
+
+    /// A speaker with `[outcomes.<outcome>]` applied, or `None` when the
+    /// outcome has no overrides and this one fits as is.
+    pub fn for_outcome(&self, outcome: Outcome) -> Result<Option<Speaker>> {
+        if !self.config.outcomes.contains_key(outcome.as_str()) {
+            return Ok(None);
+        }
+        debug!("Using {} settings", outcome);
+        Ok(Some(
+            Speaker::new(self.config.for_outcome(outcome)?)
+                .force_say(self.force_say)
+                .keep_temp(self.keep_temp),
+        ))
+    }
+
+    pub async fn speak(&self, text: &str, playback: Playback) -> Result<()> {
+        let chunks = speech_chunks(text, CHUNK_CHARS);
+        let Some((last, first)) = chunks.split_last() else {
+            return Ok(());
+        };
+        if let Some(earcon) = &self.config.earcon {
+            if let Err(e) = self.player.play_audio_file(earcon).await {
+                warn!("Failed to play earcon {}: {}", earcon.display(), e);
+            }
+        }
+        for chunk in first {
+            self.speak_chunk(chunk, Playback::UntilFinished).await?;
+        }
+        self.speak_chunk(last, playback).await
+    }
+
+    async fn speak_chunk(&self, text: &str, playback: Playback) -> Result<()> {
+        // Try the configured TTS providers in order
+        if !self.force_say && !self.tts.is_empty() {
+            match self.generate_and_play(text, playback).await {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    error!("TTS failed: {}", e);
+                    info!("Falling back to alternative methods");
+                }
+            }
+        }
+
+        // Final fallback: mac say
+        info!("Using mac say as final fallback");
+        match playback {
+            Playback::Background => self.player.say_text_background(text).await,
+            Playback::UntilFinished => self.player.say_text(text).await,
+        }
+    }
+
+    /// Play a file that outlives playback, without waiting in the background.
+    async fn play(&self, path: &Path, playback: Playback) -> Result<()> {
+        match playback {
+            Playback::Background => {
+                self.player.play_audio_file_background(path).await?;
+                Ok(())
+            }
+            Playback::UntilFinished => self.player.play_audio_file(path).await,
+        }
+    }
+
+    /// Play `path` and return once the player no longer needs the file.
+    async fn play_to_end(&self, path: &Path, playback: Playback) -> Result<()> {
+        match playback {
+            Playback::Background => {
+                self.player
+                    .play_audio_file_background(path)
+                    .await?
+                    .finished()
+                    .await
+            }
+            Playback::UntilFinished => self.player.play_audio_file(path).await,
+        }
+    }
+
+    async fn generate_and_play(&self, text: &str, playback: Playback) -> Result<()> {
+        let audio = self
+            .tts
+            .synthesize(text, &self.player.supported_formats())
+            .await?;
+        let extension = audio.format.extension();
+
+        if self.keep_temp {
+            let debug_path = std::env::current_dir()?.join(format!("debug_audio.{extension}"));
+            info!("Saving debug audio to: {}", debug_path.display());
+            audio.write_to(&debug_path).await?;
+            match self.play(&debug_path, playback).await {
+                Ok(_) => {
+                    info!(
+                        "Audio playing in background, debug file saved at: {}",
+                        debug_path.display()
+                    );
+                }
+                Err(e) => {
+                    error!("Failed to play audio: {}", e);
+                    info!("Debug file saved at: {}", debug_path.display());
+                    return Err(e);
+                }
+            }
+        } else {
+            // Use cache directory for temp files to avoid permission issues
+            self.config.ensure_cache_dir()?;
+            let temp_path = self
+                .config
+                .cache_dir
+                .join(format!("{TEMP_PREFIX}{}.{extension}", std::process::id()));
+
+            audio.write_to(&temp_path).await?;
+
+            // Hooks notify from a detached process, so waiting for the player
+            // to exit before removing the clip doesn't hold up the agent
+            let played = self.play_to_end(&temp_path, playback).await;
+            if let Err(e) = tokio::fs::remove_file(&temp_path).await {
+                warn!("Failed to remove {}: {}", temp_path.display(), e);
+            }
+            played?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove temp clips in `dir` last modified more than `older_than` ago,
+/// left behind when a process died mid-playback. Returns how many were
+/// removed.
+pub fn sweep_temp_files(dir: &Path, older_than: Duration) -> Result<usize> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(TEMP_PREFIX) {
+            continue;
+        }
+        let is_orphan = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > older_than);
+        if !is_orphan {
+            continue;
+        }
+        match std::fs::remove_file(entry.path()) {
+            Ok(()) => {
+                debug!("Removed orphaned temp audio: {}", entry.path().display());
+                removed += 1;
+            }
+            Err(e) => warn!("Failed to remove {}: {}", entry.path().display(), e),
+        }
+    }
+    Ok(removed)
+}
+
+/// Split `text` at sentence ends into chunks of at most `max_chars`; a
+/// single longer sentence becomes a chunk of its own.
+pub fn speech_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        // A sentence ends at punctuation followed by whitespace, so "1.5" stays whole
+        let end = rest
+            .char_indices()
+            .find(|&(index, c)| {
+                matches!(c, '.' | '!' | '?')
+                    && rest[index + 1..]
+                        .chars()
+                        .next()
+                        .is_none_or(char::is_whitespace)
+            })
+            .map_or(rest.len(), |(index, _)| index + 1);
+        let sentence = rest[..end].trim();
+        rest = rest[end..].trim_start();
+
+        if !current.is_empty() && current.len() + 1 + sentence.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(sentence);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_chunks_split_at_sentences() {
+        assert_eq!(speech_chunks("Tests fixed.", 300), vec!["Tests fixed."]);
+        assert!(speech_chunks("  ", 300).is_empty());
+        assert_eq!(
+            speech_chunks(
+                "One 2.5 two. Three four! A much longer third sentence? Tail",
+                20
+            ),
+            vec![
+                "One 2.5 two.",
+                "Three four!",
+                "A much longer third sentence?",
+                "Tail"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sweep_removes_only_old_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, age_secs: u64| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, b"audio").unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+                .unwrap();
+        };
+        write("temp_voice_notifier_1.mp3", 7200);
+        write("temp_voice_notifier_2.wav", 10);
+        write("summary_cache.json", 7200);
+
+        assert_eq!(sweep_temp_files(dir.path(), ORPHAN_TEMP_AGE).unwrap(), 1);
+        assert!(!dir.path().join("temp_voice_notifier_1.mp3").exists());
+        assert!(dir.path().join("temp_voice_notifier_2.wav").exists());
+        assert!(dir.path().join("summary_cache.json").exists());
+        assert_eq!(
+            sweep_temp_files(&dir.path().join("missing"), ORPHAN_TEMP_AGE).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Run `f` on the JSON state in `path` while holding an exclusive file lock,
+/// then write it back. Hook processes run concurrently, so every shared
+/// state file goes through here.
+pub fn with_locked<S, T>(path: &Path, f: impl FnOnce(&mut S) -> T) -> Result<T>
+where
+    S: Default + Serialize + DeserializeOwned,
+{
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", path.display()))?;
+
+    let mut state = read_state(&mut file, path);
+    let result = f(&mut state);
+
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(serde_json::to_string(&state)?.as_bytes())?;
+    Ok(result)
+}
+
+fn read_state<S: Default + DeserializeOwned>(file: &mut File, path: &Path) -> S {
+    let mut contents = String::new();
+    if file.read_to_string(&mut contents).is_err() || contents.trim().is_empty() {
+        return S::default();
+    }
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        debug!("Resetting unreadable state in {}: {}", path.display(), e);
+        S::default()
+    })
+}
+
+/// Milliseconds since the Unix epoch, the timestamp unit of state files.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info};
+
+use super::{ModelSettings, Summarizer, SummaryRequest};
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<Message>,
+    system: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<Content>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Content {
+    text: String,
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+pub const DEFAULT_MODEL: &str = "claude-haiku-4-5-20251001";
+
+pub struct AnthropicClient {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    settings: ModelSettings,
+}
+
+impl AnthropicClient {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key,
+            settings: ModelSettings::new(DEFAULT_MODEL),
+        }
+    }
+
+    /// Talk to a proxy or stub server instead of the public API.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_settings(mut self, settings: ModelSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Cheap authenticated request (listing one model) confirming the API
+    /// key is accepted.
+    pub async fn check_api_key(&self) -> Result<()> {
+        let response = self
+            .client
+            .get(format!("{}/v1/models?limit=1", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .timeout(self.settings.timeout)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Anthropic API error: {} - {}",
+                status,
+                error_text.trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Summarizer for AnthropicClient {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String> {
+        info!(
+            "Summarizing text with Anthropic API for event type: {}",
+            request.event
+        );
+        debug!("Text to summarize: {}", request.text);
+
+        let body = AnthropicRequest {
+            model: self.settings.model.clone(),
+            max_tokens: self.settings.max_tokens,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: request.text.to_string(),
+            }],
+            system: request.system_prompt.to_string(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .timeout(self.settings.timeout)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await?;
+            error!("Anthropic API error: {} - {}", status, error_text);
+            return Err(anyhow!("Anthropic API error: {} - {}", status, error_text));
+        }
+
+        let anthropic_response: AnthropicResponse = response.json().await?;
+
+        if let Some(content) = anthropic_response.content.first() {
+            let summary = content.text.trim().to_string();
+            info!("Generated summary: {}", summary);
+            Ok(summary)
+        } else {
+            Err(anyhow!("No content in Anthropic response"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summarize_sends_configured_model() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/messages")
+            .match_header("x-api-key", "test-key")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "claude-test",
+                "max_tokens": 40,
+            })))
+            .with_status(200)
+            .with_body(r#"{"content": [{"type": "text", "text": " Claude Code fixed the bug. "}]}"#)
+            .create_async()
+            .await;
+
+        let client = AnthropicClient::new("test-key".to_string())
+            .with_base_url(&server.url())
+            .with_settings(ModelSettings {
+                max_tokens: 40,
+                ..ModelSettings::new("claude-test")
+            });
+        let summary = client
+            .summarize(&SummaryRequest::new("long text", "Stop", "Summarize."))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(summary, "Claude Code fixed the bug.");
+    }
+}
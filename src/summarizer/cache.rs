@@ -0,0 +1,271 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::{Summarizer, SummaryRequest};
+use crate::config::Config;
+use crate::state_file::{self, now_ms};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    summary: String,
+    created_ms: u64,
+}
+
+/// Summaries on disk, so a hook firing twice for the same message doesn't
+/// pay for a second API call.
+#[derive(Debug, Clone)]
+pub struct SummaryCache {
+    path: PathBuf,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl SummaryCache {
+    pub fn new(path: PathBuf, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            path,
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// `None` when `summary_cache_ttl_secs` or `summary_cache_max_entries` is 0.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.summary_cache_ttl_secs == 0 || config.summary_cache_max_entries == 0 {
+            return None;
+        }
+        Some(Self::new(
+            config.cache_dir.join("summary_cache.json"),
+            Duration::from_secs(config.summary_cache_ttl_secs),
+            config.summary_cache_max_entries,
+        ))
+    }
+
+    /// Hash of the backend and model, the message, the event and the rendered
+    /// prompt, which stands in for the prompt version: editing a template or
+    /// the verbosity misses.
+    pub fn key(model: &str, request: &SummaryRequest<'_>) -> String {
+        let mut hasher = Sha256::new();
+        for part in [model, request.event, request.system_prompt, request.text] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        self.get_at(key, now_ms())
+    }
+
+    fn get_at(&self, key: &str, now: u64) -> Result<Option<String>> {
+        state_file::with_locked(&self.path, |state: &mut CacheState| {
+            self.evict(state, now);
+            state.entries.get(key).map(|entry| entry.summary.clone())
+        })
+    }
+
+    pub fn insert(&self, key: &str, summary: &str) -> Result<()> {
+        self.insert_at(key, summary, now_ms())
+    }
+
+    fn insert_at(&self, key: &str, summary: &str, now: u64) -> Result<()> {
+        state_file::with_locked(&self.path, |state: &mut CacheState| {
+            state.entries.insert(
+                key.to_string(),
+                Entry {
+                    summary: summary.to_string(),
+                    created_ms: now,
+                },
+            );
+            self.evict(state, now);
+        })
+    }
+
+    /// Number of live summaries, after dropping expired ones.
+    pub fn count(&self) -> Result<usize> {
+        state_file::with_locked(&self.path, |state: &mut CacheState| {
+            self.evict(state, now_ms());
+            state.entries.len()
+        })
+    }
+
+    /// Remove every summary. Returns how many were removed.
+    pub fn clear(&self) -> Result<usize> {
+        state_file::with_locked(&self.path, |state: &mut CacheState| {
+            let count = state.entries.len();
+            state.entries.clear();
+            count
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Drop expired entries, then the oldest ones over `max_entries`.
+    fn evict(&self, state: &mut CacheState, now: u64) {
+        let ttl_ms = self.ttl.as_millis() as u64;
+        state
+            .entries
+            .retain(|_, entry| now.saturating_sub(entry.created_ms) < ttl_ms);
+
+        let excess = state.entries.len().saturating_sub(self.max_entries);
+        if excess > 0 {
+            let mut by_age: Vec<(u64, String)> = state
+                .entries
+                .iter()
+                .map(|(key, entry)| (entry.created_ms, key.clone()))
+                .collect();
+            by_age.sort();
+            for (_, key) in by_age.into_iter().take(excess) {
+                state.entries.remove(&key);
+            }
+        }
+    }
+}
+
+/// A summarizer whose results are looked up in and saved to a [`SummaryCache`].
+pub struct CachedSummarizer {
+    inner: Box<dyn Summarizer>,
+    /// Part of the key, so switching models doesn't reuse old summaries
+    model: String,
+    cache: SummaryCache,
+}
+
+impl CachedSummarizer {
+    pub fn new(inner: Box<dyn Summarizer>, model: &str, cache: SummaryCache) -> Self {
+        Self {
+            model: format!("{}/{}", inner.name(), model),
+            inner,
+            cache,
+        }
+    }
+}
+
+#[async_trait]
+impl Summarizer for CachedSummarizer {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String> {
+        let key = SummaryCache::key(&self.model, request);
+        match self.cache.get(&key) {
+            Ok(Some(summary)) => {
+                debug!("Using cached summary");
+                return Ok(summary);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read summary cache: {}", e),
+        }
+
+        let summary = self.inner.summarize(request).await?;
+        if let Err(e) = self.cache.insert(&key, &summary) {
+            warn!("Failed to write summary cache: {}", e);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const MINUTE_MS: u64 = 60_000;
+
+    fn cache(dir: &tempfile::TempDir, max_entries: usize) -> SummaryCache {
+        SummaryCache::new(
+            dir.path().join("summary_cache.json"),
+            Duration::from_secs(600),
+            max_entries,
+        )
+    }
+
+    #[test]
+    fn test_ttl_and_size_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(&dir, 2);
+
+        cache.insert_at("a", "first", 0).unwrap();
+        cache.insert_at("b", "second", MINUTE_MS).unwrap();
+        assert_eq!(
+            cache.get_at("a", MINUTE_MS).unwrap().as_deref(),
+            Some("first")
+        );
+        assert_eq!(cache.get_at("a", 11 * MINUTE_MS).unwrap(), None);
+
+        cache.insert_at("c", "third", 2 * MINUTE_MS).unwrap();
+        cache.insert_at("d", "fourth", 3 * MINUTE_MS).unwrap();
+        assert_eq!(cache.get_at("b", 3 * MINUTE_MS).unwrap(), None);
+        assert!(cache.get_at("c", 3 * MINUTE_MS).unwrap().is_some());
+        assert!(cache.get_at("d", 3 * MINUTE_MS).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_key_covers_event_and_prompt() {
+        let stop = SummaryRequest::new("done", "Stop", "Be brief.");
+        let key = SummaryCache::key("anthropic/haiku", &stop);
+        assert_eq!(key, SummaryCache::key("anthropic/haiku", &stop));
+        assert_ne!(
+            key,
+            SummaryCache::key(
+                "anthropic/haiku",
+                &SummaryRequest::new("done", "Notification", "Be brief.")
+            )
+        );
+        assert_ne!(
+            key,
+            SummaryCache::key(
+                "anthropic/haiku",
+                &SummaryRequest::new("done", "Stop", "Be terse.")
+            )
+        );
+        assert_ne!(key, SummaryCache::key("ollama/llama3.2", &stop));
+    }
+
+    struct Counting(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Summarizer for Counting {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("Summary of {}", request.text))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_request_hits_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let summarizer =
+            CachedSummarizer::new(Box::new(Counting(calls.clone())), "test", cache(&dir, 10));
+        let request = SummaryRequest::new("fixed it", "Stop", "Be brief.");
+
+        assert_eq!(
+            summarizer.summarize(&request).await.unwrap(),
+            "Summary of fixed it"
+        );
+        assert_eq!(
+            summarizer.summarize(&request).await.unwrap(),
+            "Summary of fixed it"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::Config;
+
+pub mod anthropic;
+mod cache;
+mod ollama;
+mod openai;
+pub mod prompt;
+mod verbosity;
+
+pub use anthropic::AnthropicClient;
+pub use cache::{CachedSummarizer, SummaryCache};
+pub use ollama::OllamaClient;
+pub use openai::OpenAiChatClient;
+pub use verbosity::{truncate_message, Verbosity};
+
+/// Names accepted in `summarizer`.
+pub const SUMMARIZER_NAMES: [&str; 4] = ["anthropic", "openai", "ollama", "none"];
+
+/// What to summarize, for which hook event and with which rendered
+/// system prompt (see [`prompt::PromptTemplates`]).
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryRequest<'a> {
+    pub text: &'a str,
+    pub event: &'a str,
+    pub system_prompt: &'a str,
+}
+
+impl<'a> SummaryRequest<'a> {
+    pub fn new(text: &'a str, event: &'a str, system_prompt: &'a str) -> Self {
+        Self {
+            text,
+            event,
+            system_prompt,
+        }
+    }
+}
+
+/// Model parameters shared by every summarizer.
+#[derive(Debug, Clone)]
+pub struct ModelSettings {
+    pub model: String,
+    pub max_tokens: u32,
+    pub timeout: Duration,
+}
+
+impl ModelSettings {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            max_tokens: 100,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Turns an assistant message into a one or two sentence spoken summary.
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String>;
+}
+
+/// The summarizer selected by `config.summarizer`, behind the summary cache
+/// unless it is disabled, or `None` when it is disabled or missing
+/// credentials.
+pub fn from_config(config: &Config) -> Option<Box<dyn Summarizer>> {
+    let summarizer = backend_from_config(config)?;
+    let model = match summarizer.name() {
+        "anthropic" => &config.anthropic_model,
+        "openai" => &config.openai_chat_model,
+        _ => &config.ollama_model,
+    };
+    Some(match SummaryCache::from_config(config) {
+        Some(cache) => Box::new(CachedSummarizer::new(summarizer, model, cache)),
+        None => summarizer,
+    })
+}
+
+fn backend_from_config(config: &Config) -> Option<Box<dyn Summarizer>> {
+    let settings = |model: &str| ModelSettings {
+        model: model.to_string(),
+        max_tokens: config.verbosity.max_tokens(config.summary_max_tokens),
+        timeout: Duration::from_secs(config.summary_timeout_secs),
+    };
+
+    match config.summarizer.as_str() {
+        "anthropic" => config.anthropic_api_key.as_ref().map(|api_key| {
+            Box::new(
+                AnthropicClient::new(api_key.clone())
+                    .with_base_url(&config.anthropic_base_url)
+                    .with_settings(settings(&config.anthropic_model)),
+            ) as Box<dyn Summarizer>
+        }),
+        "openai" => {
+            // Needs either a key for the hosted API or an explicit local server
+            if config.openai_chat_api_key.is_none() && config.openai_chat_base_url.is_none() {
+                return None;
+            }
+            Some(Box::new(OpenAiChatClient::new(
+                config
+                    .openai_chat_base_url
+                    .clone()
+                    .unwrap_or_else(|| openai::DEFAULT_BASE_URL.to_string()),
+                config.openai_chat_api_key.clone(),
+                settings(&config.openai_chat_model),
+            )))
+        }
+        "ollama" => Some(Box::new(OllamaClient::new(
+            config.ollama_base_url.clone(),
+            settings(&config.ollama_model),
+        ))),
+        "none" => None,
+        other => {
+            warn!("Unknown summarizer '{}'", other);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(summarizer: &str) -> Config {
+        let mut config: Config = toml::from_str("").unwrap();
+        config.summarizer = summarizer.to_string();
+        config
+    }
+
+    #[test]
+    fn test_from_config_selects_summarizer() {
+        assert!(from_config(&config("anthropic")).is_none());
+        assert!(from_config(&config("openai")).is_none());
+        assert!(from_config(&config("none")).is_none());
+        assert_eq!(from_config(&config("ollama")).unwrap().name(), "ollama");
+
+        let mut anthropic = config("anthropic");
+        anthropic.anthropic_api_key = Some("key".to_string());
+        assert_eq!(from_config(&anthropic).unwrap().name(), "anthropic");
+
+        let mut local = config("openai");
+        local.openai_chat_base_url = Some("http://localhost:8080/v1".to_string());
+        assert_eq!(from_config(&local).unwrap().name(), "openai");
+    }
+}
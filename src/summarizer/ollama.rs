@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::openai::{ChatMessage, ResponseMessage};
+use super::{ModelSettings, Summarizer, SummaryRequest};
+
+#[derive(Debug, Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    num_predict: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    message: ResponseMessage,
+}
+
+/// A local Ollama server's native `/api/chat` endpoint.
+pub struct OllamaClient {
+    client: Client,
+    base_url: String,
+    settings: ModelSettings,
+}
+
+impl OllamaClient {
+    pub fn new(base_url: String, settings: ModelSettings) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            settings,
+        }
+    }
+}
+
+#[async_trait]
+impl Summarizer for OllamaClient {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String> {
+        info!(
+            "Summarizing with Ollama (model: {}) for event type: {}",
+            self.settings.model, request.event
+        );
+
+        let body = OllamaRequest {
+            model: &self.settings.model,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: request.system_prompt,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: request.text,
+                },
+            ],
+            stream: false,
+            options: OllamaOptions {
+                num_predict: self.settings.max_tokens,
+            },
+        };
+
+        debug!("Sending request to Ollama at {}", self.base_url);
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .timeout(self.settings.timeout)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to reach Ollama")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama error ({}): {}", status, error_text);
+        }
+
+        let ollama: OllamaResponse = response.json().await.context("Invalid Ollama response")?;
+        let summary = ollama.message.content.trim().to_string();
+        if summary.is_empty() {
+            anyhow::bail!("Ollama returned an empty summary");
+        }
+        info!("Generated summary: {}", summary);
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summarize_against_stub_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "llama3.2",
+                "stream": false,
+                "options": {"num_predict": 100},
+            })))
+            .with_status(200)
+            .with_body(r#"{"message": {"role": "assistant", "content": "Claude Code updated the docs.\n"}, "done": true}"#)
+            .create_async()
+            .await;
+
+        let client = OllamaClient::new(server.url(), ModelSettings::new("llama3.2"));
+        let summary = client
+            .summarize(&SummaryRequest::new("docs", "Stop", "Summarize."))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(summary, "Claude Code updated the docs.");
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::{ModelSettings, Summarizer, SummaryRequest};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+#[derive(Debug, Serialize)]
+pub(super) struct ChatMessage<'a> {
+    pub role: &'a str,
+    pub content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ResponseMessage {
+    pub content: String,
+}
+
+/// Any server speaking the OpenAI `/chat/completions` API: OpenAI itself,
+/// llama.cpp, vLLM, LM Studio and similar.
+pub struct OpenAiChatClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    settings: ModelSettings,
+}
+
+impl OpenAiChatClient {
+    pub fn new(base_url: String, api_key: Option<String>, settings: ModelSettings) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            settings,
+        }
+    }
+}
+
+#[async_trait]
+impl Summarizer for OpenAiChatClient {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn summarize(&self, request: &SummaryRequest<'_>) -> Result<String> {
+        info!(
+            "Summarizing with {} (model: {}) for event type: {}",
+            self.base_url, self.settings.model, request.event
+        );
+
+        let body = ChatRequest {
+            model: &self.settings.model,
+            max_tokens: self.settings.max_tokens,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: request.system_prompt,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: request.text,
+                },
+            ],
+        };
+
+        let mut http_request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .timeout(self.settings.timeout)
+            .json(&body);
+        // Local servers usually don't need a key
+        if let Some(api_key) = &self.api_key {
+            http_request = http_request.bearer_auth(api_key);
+        }
+
+        debug!("Sending request to OpenAI-compatible chat API");
+        let response = http_request
+            .send()
+            .await
+            .context("Failed to send request to chat API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Chat API error ({}): {}", status, error_text);
+        }
+
+        let chat: ChatResponse = response.json().await.context("Invalid chat response")?;
+        let summary = chat
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content.trim().to_string())
+            .filter(|summary| !summary.is_empty())
+            .context("No content in chat response")?;
+        info!("Generated summary: {}", summary);
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summarize_against_stub_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "qwen2.5",
+                "max_tokens": 60,
+                "messages": [{"role": "system"}, {"role": "user", "content": "did things"}],
+            })))
+            .with_status(200)
+            .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "Codex did things."}}]}"#)
+            .create_async()
+            .await;
+
+        let client = OpenAiChatClient::new(
+            format!("{}/v1/", server.url()),
+            Some("test-key".to_string()),
+            ModelSettings {
+                max_tokens: 60,
+                ..ModelSettings::new("qwen2.5")
+            },
+        );
+        let summary = client
+            .summarize(&SummaryRequest::new("did things", "Stop", "Summarize."))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(summary, "Codex did things.");
+    }
+
+    #[tokio::test]
+    async fn test_empty_choices_are_an_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_body(r#"{"choices": []}"#)
+            .create_async()
+            .await;
+
+        let client = OpenAiChatClient::new(server.url(), None, ModelSettings::new("local"));
+        assert!(client
+            .summarize(&SummaryRequest::new("text", "Stop", "Summarize."))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::Verbosity;
+use crate::config::Config;
+use crate::transcript::Outcome;
+
+const STOP_TEMPLATE: &str = include_str!("prompts/stop.txt");
+const NOTIFICATION_TEMPLATE: &str = include_str!("prompts/notification.txt");
+
+/// Values a template can use as `{{name}}`. A `{{#name}}...{{/name}}`
+/// section is kept only when `name` is non-empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PromptContext<'a> {
+    pub agent_name: &'a str,
+    pub event: &'a str,
+    pub project: Option<&'a str>,
+    /// Message from a Notification hook, e.g. the permission being requested
+    pub message: Option<&'a str>,
+    /// How long the turn took
+    pub duration: Option<Duration>,
+    pub files_changed: &'a [String],
+    /// What the transcript shows was done, from `TaskDigest::facts`
+    pub digest: &'a [String],
+    /// How the task ended, going by heuristics
+    pub outcome: Option<Outcome>,
+    /// Whether to ask for an outcome tag before the summary
+    pub classify: bool,
+    pub verbosity: Verbosity,
+}
+
+impl PromptContext<'_> {
+    /// `None` for names templates don't know about.
+    fn variable(&self, name: &str) -> Option<String> {
+        Some(match name {
+            "agent_name" => self.agent_name.to_string(),
+            "event" => self.event.to_string(),
+            "project" => self.project.unwrap_or_default().to_string(),
+            "message" => self.message.unwrap_or_default().to_string(),
+            "duration" => self.duration.map(spoken_duration).unwrap_or_default(),
+            "files_changed" => self
+                .files_changed
+                .iter()
+                .map(|path| {
+                    path.rsplit(['/', '\\'])
+                        .next()
+                        .unwrap_or(path.as_str())
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            "digest" => self.digest.join("; "),
+            "outcome" => self
+                .outcome
+                .map(|outcome| outcome.to_string())
+                .unwrap_or_default(),
+            "classify" => {
+                if self.classify {
+                    name.to_string()
+                } else {
+                    String::new()
+                }
+            }
+            "verbosity" => self.verbosity.to_string(),
+            // Sections: `{{#terse}}...{{/terse}}` applies only to terse summaries
+            "terse" | "normal" | "detailed" => {
+                if self.verbosity.as_str() == name {
+                    name.to_string()
+                } else {
+                    String::new()
+                }
+            }
+            "files_changed_count" => match self.files_changed.len() {
+                0 => String::new(),
+                count => count.to_string(),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// "45 seconds", "3 minutes", "1 hour 5 minutes".
+fn spoken_duration(duration: Duration) -> String {
+    let unit =
+        |count: u64, name: &str| format!("{count} {name}{}", if count == 1 { "" } else { "s" });
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => unit(seconds, "second"),
+        60..=3599 => unit(seconds / 60, "minute"),
+        _ if seconds % 3600 < 60 => unit(seconds / 3600, "hour"),
+        _ => format!(
+            "{} {}",
+            unit(seconds / 3600, "hour"),
+            unit(seconds % 3600 / 60, "minute")
+        ),
+    }
+}
+
+/// The template shipped with voice-notifier for `event`.
+pub fn builtin_template(event: &str) -> &'static str {
+    match event {
+        "Notification" => NOTIFICATION_TEMPLATE,
+        _ => STOP_TEMPLATE,
+    }
+}
+
+/// System prompt templates, looked up per event type.
+#[derive(Debug, Clone)]
+pub struct PromptTemplates {
+    /// `[prompts]` from config files, already merged per project
+    overrides: HashMap<String, String>,
+    dir: PathBuf,
+}
+
+impl PromptTemplates {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            overrides: config.prompts.clone(),
+            dir: config.prompt_dir.clone(),
+        }
+    }
+
+    /// `prompts.<Event>` from config, else `<prompt_dir>/<event>.txt`, else
+    /// the built-in template.
+    pub fn template(&self, event: &str) -> String {
+        if let Some(template) = self.overrides.get(event) {
+            debug!("Using configured prompt for {}", event);
+            return template.clone();
+        }
+
+        let path = self.dir.join(format!("{}.txt", event.to_lowercase()));
+        match std::fs::read_to_string(&path) {
+            Ok(template) => {
+                debug!("Using prompt template {}", path.display());
+                template
+            }
+            Err(_) => builtin_template(event).to_string(),
+        }
+    }
+
+    pub fn render(&self, context: &PromptContext) -> String {
+        render(&self.template(context.event), context)
+    }
+}
+
+/// Fill in `{{name}}` variables and `{{#name}}...{{/name}}` sections.
+/// Unknown variables are left as written. Section tags on lines of their own
+/// are removed along with their line breaks.
+pub fn render(template: &str, context: &PromptContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let tag = after_open[..end].trim();
+        let after_tag = &after_open[end + 2..];
+
+        if let Some(name) = tag.strip_prefix('#') {
+            let name = name.trim();
+            let close = format!("{{{{/{name}}}}}");
+            let Some(close_at) = after_tag.find(&close) else {
+                warn!("Unclosed section '{}' in prompt template", name);
+                out.push_str(&rest[start..]);
+                return out;
+            };
+            let block = after_tag.starts_with('\n');
+            let inner = if block {
+                &after_tag[1..close_at]
+            } else {
+                &after_tag[..close_at]
+            };
+            if context
+                .variable(name)
+                .is_some_and(|value| !value.is_empty())
+            {
+                out.push_str(&render(inner, context));
+            }
+            let after_close = &after_tag[close_at + close.len()..];
+            rest = if block {
+                after_close.strip_prefix('\n').unwrap_or(after_close)
+            } else {
+                after_close
+            };
+            continue;
+        }
+
+        match context.variable(tag) {
+            Some(value) => out.push_str(&value),
+            None => {
+                warn!("Unknown prompt variable '{}'", tag);
+                out.push_str(&rest[start..start + 2 + end + 2]);
+            }
+        }
+        rest = after_tag;
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(message: Option<&'static str>) -> PromptContext<'static> {
+        PromptContext {
+            agent_name: "Claude Code",
+            event: "Notification",
+            project: Some("api"),
+            message,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sections_and_variables() {
+        let template = "Hi {{agent_name}} in {{ project }}.\n{{#message}}\nContext: {{message}}\n{{/message}}\nEnd {{unknown}}";
+        assert_eq!(
+            render(template, &context(Some("approve rm"))),
+            "Hi Claude Code in api.\nContext: approve rm\nEnd {{unknown}}"
+        );
+        assert_eq!(
+            render(template, &context(None)),
+            "Hi Claude Code in api.\nEnd {{unknown}}"
+        );
+        assert_eq!(
+            render("A{{#message}} ({{message}}){{/message}}\nB", &context(None)),
+            "A\nB"
+        );
+    }
+
+    #[test]
+    fn test_builtin_notification_prompt() {
+        let prompt = render(
+            builtin_template("Notification"),
+            &context(Some("Run tests?")),
+        );
+        assert!(prompt.contains("final response.\n\nNotification context: Run tests?\n\nCRITICAL"));
+        assert!(prompt.contains("2. Must start with 'Claude Code'"));
+
+        let prompt = render(builtin_template("Notification"), &context(None));
+        assert!(prompt.contains("final response.\n\nCRITICAL"));
+    }
+
+    #[test]
+    fn test_verbosity_sections() {
+        let terse = PromptContext {
+            event: "Stop",
+            verbosity: Verbosity::Terse,
+            ..context(None)
+        };
+        let prompt = render(builtin_template("Stop"), &terse);
+        assert!(prompt.contains("RULES:\n1. Output AT MOST 6 words."));
+        assert!(prompt.contains("- 'API tests fixed'"));
+        assert!(!prompt.contains("1-2 sentences"));
+        assert!(!prompt.contains("{{"));
+    }
+
+    #[test]
+    fn test_override_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("stop.txt"), "File prompt").unwrap();
+        let mut templates = PromptTemplates {
+            overrides: HashMap::new(),
+            dir: dir.path().to_path_buf(),
+        };
+        assert_eq!(templates.template("Stop"), "File prompt");
+        assert_eq!(
+            templates.template("Notification"),
+            builtin_template("Notification")
+        );
+
+        templates
+            .overrides
+            .insert("Stop".to_string(), "Say done.".to_string());
+        assert_eq!(templates.template("Stop"), "Say done.");
+    }
+
+    #[test]
+    fn test_turn_variables() {
+        let files = ["/repo/src/main.rs".to_string(), "README.md".to_string()];
+        let digest = [
+            "edited 2 files".to_string(),
+            "ran the tests, all 3 passed".to_string(),
+        ];
+        let context = PromptContext {
+            duration: Some(Duration::from_secs(3_900)),
+            files_changed: &files,
+            digest: &digest,
+            ..context(None)
+        };
+        assert_eq!(
+            render(
+                "{{duration}}; {{files_changed}} ({{files_changed_count}})",
+                &context
+            ),
+            "1 hour 5 minutes; main.rs, README.md (2)"
+        );
+        assert_eq!(
+            render("{{digest}}", &context),
+            "edited 2 files; ran the tests, all 3 passed"
+        );
+        assert_eq!(spoken_duration(Duration::from_secs(61)), "1 minute");
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Words kept by the terse fallback.
+const TERSE_WORDS: usize = 6;
+/// Characters kept by the normal fallback when there is no short first sentence.
+const NORMAL_CHARS: usize = 100;
+/// Characters of whole sentences kept by the detailed fallback.
+const DETAILED_CHARS: usize = 400;
+
+/// How long spoken summaries are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    /// A few words, e.g. "API tests fixed"
+    Terse,
+    /// One or two sentences
+    #[default]
+    Normal,
+    /// A short paragraph
+    Detailed,
+}
+
+impl Verbosity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verbosity::Terse => "terse",
+            Verbosity::Normal => "normal",
+            Verbosity::Detailed => "detailed",
+        }
+    }
+
+    /// Scale `summary_max_tokens`, which is sized for normal summaries.
+    pub fn max_tokens(&self, normal: u32) -> u32 {
+        match self {
+            Verbosity::Terse => normal.min(30),
+            Verbosity::Normal => normal,
+            Verbosity::Detailed => normal * 3,
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "terse" => Ok(Verbosity::Terse),
+            "normal" => Ok(Verbosity::Normal),
+            "detailed" => Ok(Verbosity::Detailed),
+            other => Err(anyhow::anyhow!(
+                "Unknown verbosity '{}' (expected terse, normal or detailed)",
+                other
+            )),
+        }
+    }
+}
+
+/// Fallback summary when no summarizer is available: the start of `message`,
+/// cut to roughly the length `verbosity` asks for.
+pub fn truncate_message(message: &str, verbosity: Verbosity) -> String {
+    let trimmed = message.trim();
+
+    match verbosity {
+        Verbosity::Terse => {
+            let sentence = trimmed
+                .split(['.', '!', '?', '\n'])
+                .next()
+                .unwrap_or_default();
+            sentence
+                .split_whitespace()
+                .take(TERSE_WORDS)
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+        Verbosity::Normal => {
+            // Try to find first sentence
+            if let Some(end) = trimmed.find(['.', '!', '?']) {
+                let sentence = &trimmed[..=end];
+                if sentence.len() <= 150 {
+                    return sentence.to_string();
+                }
+            }
+            cut_at_word(trimmed, NORMAL_CHARS)
+        }
+        Verbosity::Detailed => {
+            // As many whole sentences as fit
+            let end = trimmed
+                .match_indices(['.', '!', '?'])
+                .map(|(index, _)| index + 1)
+                .take_while(|end| *end <= DETAILED_CHARS)
+                .last();
+            match end {
+                Some(end) => trimmed[..end].to_string(),
+                None => cut_at_word(trimmed, DETAILED_CHARS),
+            }
+        }
+    }
+}
+
+/// `text` cut at the last word boundary before `max_chars`, with "...".
+fn cut_at_word(text: &str, max_chars: usize) -> String {
+    let Some((limit, _)) = text.char_indices().nth(max_chars) else {
+        return text.to_string();
+    };
+    let truncated = &text[..limit];
+    match truncated.rfind(' ') {
+        Some(last_space) => format!("{}...", &truncated[..last_space]),
+        None => format!("{truncated}..."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str =
+        "Fixed the flaky API tests by mocking the clock. Also renamed the helpers. \
+         The CI pipeline is green again.";
+
+    #[test]
+    fn test_truncate_per_verbosity() {
+        assert_eq!(
+            truncate_message(MESSAGE, Verbosity::Terse),
+            "Fixed the flaky API tests by"
+        );
+        assert_eq!(
+            truncate_message(MESSAGE, Verbosity::Normal),
+            "Fixed the flaky API tests by mocking the clock."
+        );
+        assert_eq!(truncate_message(MESSAGE, Verbosity::Detailed), MESSAGE);
+
+        let long = "word ".repeat(200);
+        assert!(truncate_message(&long, Verbosity::Detailed).len() <= DETAILED_CHARS + 3);
+        assert!(truncate_message(&long, Verbosity::Normal).ends_with("..."));
+    }
+
+    #[test]
+    fn test_parse_and_tokens() {
+        assert_eq!("Terse".parse::<Verbosity>().unwrap(), Verbosity::Terse);
+        assert!("chatty".parse::<Verbosity>().is_err());
+        assert_eq!(Verbosity::Detailed.max_tokens(100), 300);
+        assert_eq!(Verbosity::Terse.max_tokens(100), 30);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::format::{file_call, shell_call};
+use super::turn::TurnBuilder;
+use super::{LastTurn, ReverseLines};
+
+/// Prefix of the lines holding what the user typed.
+const PROMPT_PREFIX: &str = "#### ";
+
+/// The last turn of an Aider `.aider.chat.history.md`, read from the end of
+/// the file back to the prompt.
+pub(super) fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let file = fs::File::open(transcript_path)
+        .with_context(|| format!("Failed to open transcript file: {transcript_path:?}"))?;
+    let mut lines = Vec::new();
+    let mut in_prompt = false;
+    for line in ReverseLines::new(file).context("Failed to seek transcript file")? {
+        let line = line.context("Failed to read line")?;
+        // A prompt of several lines has the prefix on each
+        let is_prompt = line.starts_with(PROMPT_PREFIX);
+        if in_prompt && !is_prompt {
+            break;
+        }
+        in_prompt = is_prompt;
+        lines.push(line);
+    }
+    lines.reverse();
+    Ok(parse_turn(&lines))
+}
+
+/// Build the turn from the history's lines: `#### ` prompt lines, `> `
+/// quoted tool output ("Applied edit to ...", "Running ..."), and the
+/// model's Markdown reply, whose fenced edit blocks are left out.
+fn parse_turn(lines: &[String]) -> LastTurn {
+    let mut builder = TurnBuilder::default();
+    let request: Vec<&str> = lines
+        .iter()
+        .map_while(|line| line.strip_prefix(PROMPT_PREFIX).map(str::trim_end))
+        .collect();
+    if !request.is_empty() {
+        builder.prompt(Some(request.join("\n")), None);
+    }
+
+    let mut reply = Vec::new();
+    let mut in_fence = false;
+    let mut created = HashSet::new();
+    // The command being run and the output quoted after it
+    let mut shell: Option<(&str, Vec<&str>)> = None;
+    for line in &lines[request.len()..] {
+        // Quoted lines end in two spaces, Markdown's line break
+        let line = line.trim_end();
+        if let Some(note) = line.strip_prefix('>').filter(|_| !in_fence) {
+            let note = note.trim();
+            if let Some(command) = note.strip_prefix("Running ") {
+                finish_command(&mut builder, shell.replace((command, Vec::new())));
+            } else if let Some(path) = note.strip_prefix("Creating empty file ") {
+                finish_command(&mut builder, shell.take());
+                created.insert(path);
+            } else if let Some(path) = note.strip_prefix("Applied edit to ") {
+                finish_command(&mut builder, shell.take());
+                builder.tool_call(None, file_call(path, created.contains(path)));
+            } else if let Some((_, output)) = &mut shell {
+                output.push(note);
+            }
+            continue;
+        }
+        finish_command(&mut builder, shell.take());
+
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && !line.starts_with("# aider chat started") {
+            reply.push(line);
+        }
+    }
+    finish_command(&mut builder, shell);
+    builder.text(reply.join("\n").trim());
+    builder.finish()
+}
+
+fn finish_command(builder: &mut TurnBuilder, shell: Option<(&str, Vec<&str>)>) {
+    if let Some((command, output)) = shell {
+        let mut call = shell_call(command);
+        call.output = Some(output.join("\n"));
+        builder.tool_call(None, call);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TaskDigest;
+
+    #[test]
+    fn test_read_chat_history() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            "\n# aider chat started at 2025-06-01 10:00:00\n\n\
+             > Aider v0.86.0  \n\n\
+             #### fix the off-by-one in pagination  \n\
+             #### and add a test  \n\n\
+             The last page was skipped. Here are the changes:\n\n\
+             app/pages.py\n```python\n<<<<<<< SEARCH\n    return n // size\n=======\n    return -(-n // size)\n>>>>>>> REPLACE\n```\n\n\
+             tests/test_pages.py\n```python\n<<<<<<< SEARCH\n=======\ndef test_last_page(): ...\n>>>>>>> REPLACE\n```\n\n\
+             > Creating empty file tests/test_pages.py  \n\
+             > Applied edit to tests/test_pages.py  \n\
+             > Applied edit to app/pages.py  \n\
+             > Running pytest  \n\
+             > ===== 8 passed in 0.30s =====  \n",
+        )
+        .unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(
+            turn.request.as_deref(),
+            Some("fix the off-by-one in pagination\nand add a test")
+        );
+        assert_eq!(
+            turn.final_text.as_deref(),
+            Some("The last page was skipped. Here are the changes:\napp/pages.py\ntests/test_pages.py")
+        );
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_created, ["tests/test_pages.py"]);
+        assert_eq!(digest.files_edited, ["app/pages.py"]);
+        assert_eq!(digest.tests.unwrap().passed, 8);
+    }
+}
@@ -0,0 +1,278 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use super::format::{patch_calls, shell_call, shell_result, tool_call};
+use super::model::lenient_timestamp;
+use super::turn::TurnBuilder;
+use super::{read_tail, LastTurn, ToolCall};
+
+/// User messages Codex writes itself, as opposed to prompts.
+const INJECTED_PREFIXES: [&str; 4] = [
+    "<environment_context>",
+    "<user_instructions>",
+    "<turn_aborted>",
+    "# AGENTS.md instructions",
+];
+
+/// Functions that run a shell command.
+const SHELL_FUNCTIONS: [&str; 5] = [
+    "shell",
+    "shell_command",
+    "local_shell",
+    "container.exec",
+    "exec_command",
+];
+
+/// A rollout line. Newer files wrap each item with a timestamp; older ones
+/// write response items bare.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default, deserialize_with = "lenient_timestamp")]
+    timestamp: Option<OffsetDateTime>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    payload: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Item {
+    Message {
+        role: String,
+        #[serde(default)]
+        content: Vec<Part>,
+    },
+    FunctionCall {
+        name: String,
+        /// JSON arguments, as a string
+        #[serde(default)]
+        arguments: String,
+        #[serde(default)]
+        call_id: String,
+    },
+    /// Freeform tools such as `apply_patch`, whose input is raw text
+    CustomToolCall {
+        name: String,
+        #[serde(default)]
+        input: String,
+        #[serde(default)]
+        call_id: String,
+    },
+    LocalShellCall {
+        #[serde(default)]
+        call_id: String,
+        #[serde(default)]
+        action: Value,
+    },
+    #[serde(alias = "custom_tool_call_output")]
+    FunctionCallOutput {
+        #[serde(default)]
+        call_id: String,
+        #[serde(default)]
+        output: Value,
+    },
+    /// Reasoning, web searches and item types added later
+    #[serde(other)]
+    Other,
+}
+
+/// An `input_text` or `output_text` part of a message.
+#[derive(Debug, Deserialize)]
+struct Part {
+    #[serde(default)]
+    text: String,
+}
+
+struct Line {
+    timestamp: Option<OffsetDateTime>,
+    item: Item,
+}
+
+impl Line {
+    /// `None` for event and metadata lines, which repeat what the response
+    /// items say.
+    fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line.trim()).ok()?;
+        let envelope = Envelope::deserialize(&value).ok()?;
+        let item = match (envelope.kind.as_deref(), envelope.payload) {
+            (Some("response_item"), Some(payload)) => payload,
+            (_, Some(_)) => return None,
+            (_, None) => value,
+        };
+        Some(Line {
+            timestamp: envelope.timestamp,
+            item: serde_json::from_value(item).ok()?,
+        })
+    }
+
+    fn prompt(&self) -> Option<String> {
+        match &self.item {
+            Item::Message { role, content } if role == "user" => {
+                let text = message_text(content);
+                let injected = INJECTED_PREFIXES
+                    .iter()
+                    .any(|prefix| text.trim_start().starts_with(prefix));
+                (!injected).then_some(text)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The last turn of a Codex CLI rollout file.
+pub(super) fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let lines = read_tail(transcript_path, 1, Line::parse, |line| {
+        line.prompt().is_some()
+    })?;
+
+    let mut builder = TurnBuilder::default();
+    for line in lines {
+        if let Some(request) = line.prompt() {
+            builder.prompt(Some(request), line.timestamp);
+            continue;
+        }
+        builder.seen(line.timestamp);
+        match line.item {
+            Item::Message { role, content } if role == "assistant" => {
+                builder.text(&message_text(&content));
+            }
+            Item::FunctionCall {
+                name,
+                arguments,
+                call_id,
+            } => {
+                let arguments = serde_json::from_str(&arguments).unwrap_or_default();
+                builder.tool_calls(Some(&call_id), function_calls(&name, arguments));
+            }
+            Item::CustomToolCall {
+                name,
+                input,
+                call_id,
+            } => {
+                let calls = match name.as_str() {
+                    "apply_patch" => patch_calls(&input),
+                    _ => vec![tool_call(&name, Value::String(input))],
+                };
+                builder.tool_calls(Some(&call_id), calls);
+            }
+            Item::LocalShellCall { call_id, action } => {
+                builder.tool_calls(Some(&call_id), function_calls("local_shell", action));
+            }
+            Item::FunctionCallOutput { call_id, output } => {
+                let (output, is_error) = call_output(&output);
+                builder.tool_result(&call_id, output, is_error);
+            }
+            _ => {}
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn message_text(content: &[Part]) -> String {
+    content
+        .iter()
+        .map(|part| part.text.as_str())
+        .filter(|text| !text.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Shell commands and patches as Claude Code's tools; other functions as is.
+fn function_calls(name: &str, arguments: Value) -> Vec<ToolCall> {
+    if name == "apply_patch" {
+        return patch_calls(arguments["input"].as_str().unwrap_or_default());
+    }
+    if !SHELL_FUNCTIONS.contains(&name) {
+        return vec![tool_call(name, arguments)];
+    }
+    let command = match &arguments["command"] {
+        Value::Array(words) => {
+            let words: Vec<&str> = words.iter().filter_map(Value::as_str).collect();
+            match words.as_slice() {
+                ["apply_patch", patch] => return patch_calls(patch),
+                [_, "-lc" | "-c", script] => script.to_string(),
+                words => words.join(" "),
+            }
+        }
+        Value::String(command) => command.clone(),
+        _ => arguments["cmd"].as_str().unwrap_or_default().to_string(),
+    };
+    // Patches are often applied through a heredoc
+    if command.trim_start().starts_with("apply_patch") {
+        return patch_calls(&command);
+    }
+    vec![shell_call(&command)]
+}
+
+/// Output text and whether the call failed. Older rollouts store a JSON
+/// string of `{output, metadata: {exit_code}}`, newer ones text starting
+/// with "Exit code: N".
+fn call_output(output: &Value) -> (String, bool) {
+    let structured = match output {
+        Value::String(text) => serde_json::from_str::<Value>(text)
+            .ok()
+            .filter(Value::is_object),
+        Value::Object(_) => Some(output.clone()),
+        _ => None,
+    };
+    if let Some(structured) = structured {
+        return shell_result(
+            structured["output"].as_str().unwrap_or_default(),
+            structured["metadata"]["exit_code"].as_i64(),
+        );
+    }
+
+    let text = output.as_str().unwrap_or_default();
+    let (first_line, rest) = text.split_once('\n').unwrap_or((text, ""));
+    match first_line
+        .strip_prefix("Exit code:")
+        .and_then(|code| code.trim().parse().ok())
+    {
+        Some(code) => shell_result(rest, Some(code)),
+        None => (text.to_string(), false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TaskDigest;
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    fn test_read_rollout_turn() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let lines = [
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"s1","cwd":"/repo"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Add a health check"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"event_msg","payload":{"type":"user_message","message":"Add a health check"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:05Z","type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","call_id":"c1","input":"*** Begin Patch\n*** Add File: src/health.rs\n+pub fn ok() {}\n*** Update File: src/lib.rs\n+mod health;\n*** End Patch"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:06Z","type":"response_item","payload":{"type":"custom_tool_call_output","call_id":"c1","output":"{\"output\":\"Success. Updated the following files:\\nA src/health.rs\\nM src/lib.rs\\n\",\"metadata\":{\"exit_code\":0}}"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:10Z","type":"response_item","payload":{"type":"function_call","name":"shell","call_id":"c2","arguments":"{\"command\":[\"bash\",\"-lc\",\"cargo test\"],\"workdir\":\"/repo\"}"}}"#,
+            r#"{"timestamp":"2025-09-01T10:01:00Z","type":"response_item","payload":{"type":"function_call_output","call_id":"c2","output":"Exit code: 101\nWall time: 50 seconds\nOutput:\ntest result: FAILED. 4 passed; 1 failed"}}"#,
+            r#"{"timestamp":"2025-09-01T10:01:30Z","type":"response_item","payload":{"type":"reasoning","summary":[]}}"#,
+            r#"{"timestamp":"2025-09-01T10:01:30Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Added the health check; one test still fails."}]}}"#,
+        ];
+        writeln!(file, "{}", lines.join("\n")).unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(turn.request.as_deref(), Some("Add a health check"));
+        assert_eq!(turn.duration, Some(Duration::from_secs(89)));
+        assert_eq!(
+            turn.final_text.as_deref(),
+            Some("Added the health check; one test still fails.")
+        );
+
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_created, ["src/health.rs"]);
+        assert_eq!(digest.files_edited, ["src/lib.rs"]);
+        assert_eq!(digest.commands[0].command, "cargo test");
+        assert_eq!(digest.commands[0].exit_code, Some(101));
+        assert_eq!(digest.tests.unwrap().failed, 1);
+    }
+}
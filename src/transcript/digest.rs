@@ -0,0 +1,387 @@
+use std::time::Duration;
+
+use super::{LastTurn, ToolCall};
+
+/// Tools whose `file_path` (or `notebook_path`) input is a changed file.
+const EDIT_TOOLS: [&str; 4] = ["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Shell tools whose `command` input is a command line.
+const SHELL_TOOLS: [&str; 2] = ["Bash", "PowerShell"];
+
+/// Words in a command line that mark it as a test run.
+const TEST_RUNNERS: [&str; 8] = [
+    "test", "tests", "pytest", "jest", "vitest", "mocha", "rspec", "nextest",
+];
+
+/// A shell command run during the turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandRun {
+    pub command: String,
+    /// `None` when no result was recorded, e.g. the command was interrupted
+    pub exit_code: Option<i32>,
+}
+
+impl CommandRun {
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|code| code != 0)
+    }
+
+    /// Whether the command runs a test suite, e.g. `cargo test` or `npm run test:unit`.
+    pub fn is_test(&self) -> bool {
+        self.command
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .map(|word| word.rsplit('/').next().unwrap_or(word))
+            .any(|word| TEST_RUNNERS.contains(&word))
+    }
+}
+
+/// Test results of the turn, from the last run of each distinct test
+/// command, so a suite that failed and was fixed counts as passing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub passed: u32,
+    pub failed: u32,
+    /// A test command exited non-zero, even if no failures were counted
+    pub run_failed: bool,
+}
+
+impl TestCounts {
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0 && !self.run_failed
+    }
+}
+
+/// What the last turn did, read from its tool calls without an LLM.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskDigest {
+    /// Files written for the first time, in order
+    pub files_created: Vec<String>,
+    /// Existing files changed, in first-touched order
+    pub files_edited: Vec<String>,
+    pub commands: Vec<CommandRun>,
+    /// `None` when no tests were run
+    pub tests: Option<TestCounts>,
+    pub elapsed: Option<Duration>,
+}
+
+impl TaskDigest {
+    pub fn from_turn(turn: &LastTurn) -> Self {
+        let mut digest = TaskDigest {
+            elapsed: turn.duration,
+            ..Default::default()
+        };
+
+        // The latest counts per test command line, in first-run order
+        let mut test_runs: Vec<(&str, TestCounts)> = Vec::new();
+        for call in &turn.tool_calls {
+            if EDIT_TOOLS.contains(&call.name.as_str()) {
+                digest.add_file(call);
+            } else if SHELL_TOOLS.contains(&call.name.as_str()) {
+                let Some(command) = call.input["command"].as_str() else {
+                    continue;
+                };
+                let run = CommandRun {
+                    command: command.to_string(),
+                    exit_code: exit_code(call),
+                };
+                if run.is_test() {
+                    let (passed, failed) = count_tests(call.output.as_deref().unwrap_or_default());
+                    let counts = TestCounts {
+                        passed,
+                        failed,
+                        run_failed: run.failed(),
+                    };
+                    match test_runs
+                        .iter_mut()
+                        .find(|(earlier, _)| *earlier == command)
+                    {
+                        Some((_, latest)) => *latest = counts,
+                        None => test_runs.push((command, counts)),
+                    }
+                }
+                digest.commands.push(run);
+            }
+        }
+
+        digest.tests = test_runs
+            .into_iter()
+            .map(|(_, counts)| counts)
+            .reduce(|total, counts| TestCounts {
+                passed: total.passed + counts.passed,
+                failed: total.failed + counts.failed,
+                run_failed: total.run_failed || counts.run_failed,
+            });
+        digest
+    }
+
+    fn add_file(&mut self, call: &ToolCall) {
+        let Some(path) = call.input["file_path"]
+            .as_str()
+            .or_else(|| call.input["notebook_path"].as_str())
+        else {
+            return;
+        };
+        let known = |files: &[String]| files.iter().any(|file| file == path);
+        if known(&self.files_created) || known(&self.files_edited) {
+            return;
+        }
+        // Claude Code reports "File created successfully at: ..." for new files
+        let created = call.name == "Write"
+            && call
+                .output
+                .as_deref()
+                .is_some_and(|output| output.trim_start().starts_with("File created"));
+        if created {
+            self.files_created.push(path.to_string());
+        } else {
+            self.files_edited.push(path.to_string());
+        }
+    }
+
+    /// Created files, then edited ones.
+    pub fn files_changed(&self) -> Vec<String> {
+        self.files_created
+            .iter()
+            .chain(&self.files_edited)
+            .cloned()
+            .collect()
+    }
+
+    /// Commands that failed, other than test runs.
+    pub fn failed_commands(&self) -> impl Iterator<Item = &CommandRun> {
+        self.commands
+            .iter()
+            .filter(|command| command.failed() && !command.is_test())
+    }
+
+    /// Spoken clauses such as "edited 4 files" and "ran the tests, all 12
+    /// passed"; empty when the turn changed and ran nothing.
+    pub fn facts(&self) -> Vec<String> {
+        let mut facts = Vec::new();
+
+        let files = [
+            ("created", self.files_created.len()),
+            ("edited", self.files_edited.len()),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(verb, count)| format!("{verb} {}", plural(count, "file")))
+        .collect::<Vec<_>>();
+        if !files.is_empty() {
+            facts.push(files.join(" and "));
+        }
+
+        if let Some(tests) = &self.tests {
+            facts.push(match (tests.failed, tests.passed) {
+                (0, _) if tests.run_failed => "ran the tests, which failed".to_string(),
+                (0, 0) => "ran the tests, which passed".to_string(),
+                (0, passed) => format!("ran the tests, all {passed} passed"),
+                (failed, _) => format!("ran the tests, {failed} failed"),
+            });
+        }
+
+        match self.failed_commands().count() {
+            0 => {}
+            1 => facts.push("saw 1 command fail".to_string()),
+            count => facts.push(format!("saw {count} commands fail")),
+        }
+        facts
+    }
+
+    /// Fallback sentence such as "Claude Code edited 4 files; ran the tests,
+    /// all 12 passed". `None` when there is nothing to report.
+    pub fn describe(&self, agent_name: &str) -> Option<String> {
+        let facts = self.facts();
+        (!facts.is_empty()).then(|| format!("{agent_name} {}", facts.join("; ")))
+    }
+}
+
+/// Exit code from a shell tool result. Claude Code starts failed results
+/// with "Exit code N"; a result without an error is a success.
+fn exit_code(call: &ToolCall) -> Option<i32> {
+    let output = call.output.as_deref()?;
+    if !call.is_error {
+        return Some(0);
+    }
+    let first_line = output.trim_start().lines().next().unwrap_or_default();
+    let first_line = first_line.strip_prefix("Error: ").unwrap_or(first_line);
+    Some(
+        first_line
+            .strip_prefix("Exit code ")
+            .and_then(|code| code.trim().parse().ok())
+            .unwrap_or(1),
+    )
+}
+
+/// Passed and failed counts from test runner output: "N passed" and
+/// "N failed" in cargo, pytest, jest and vitest summaries, "N passing" and
+/// "N failing" in mocha.
+fn count_tests(output: &str) -> (u32, u32) {
+    let mut passed = 0;
+    let mut failed = 0;
+    for line in output.lines() {
+        let line = line.trim();
+        // jest and vitest also count suites and files; only count tests
+        if line.starts_with("Test Suites:") || line.starts_with("Test Files") {
+            continue;
+        }
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '='))
+            .filter(|word| !word.is_empty())
+            .collect();
+        for pair in words.windows(2) {
+            let Ok(count) = pair[0].parse::<u32>() else {
+                continue;
+            };
+            let word = pair[1].trim_end_matches('.').to_lowercase();
+            if word.starts_with("pass") {
+                passed += count;
+            } else if word.starts_with("fail") {
+                failed += count;
+            }
+        }
+    }
+    (passed, failed)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(name: &str, input: serde_json::Value, output: &str, is_error: bool) -> ToolCall {
+        ToolCall {
+            name: name.to_string(),
+            input,
+            output: Some(output.to_string()),
+            is_error,
+        }
+    }
+
+    #[test]
+    fn test_digest_from_turn() {
+        let turn = LastTurn {
+            tool_calls: vec![
+                call(
+                    "Write",
+                    json!({"file_path": "src/new.rs"}),
+                    "File created successfully at: src/new.rs",
+                    false,
+                ),
+                call(
+                    "Edit",
+                    json!({"file_path": "src/lib.rs"}),
+                    "The file src/lib.rs has been updated.",
+                    false,
+                ),
+                call("Edit", json!({"file_path": "src/new.rs"}), "", false),
+                call(
+                    "Bash",
+                    json!({"command": "cargo build"}),
+                    "Exit code 101\nerror[E0425]",
+                    true,
+                ),
+                call(
+                    "Bash",
+                    json!({"command": "cargo test"}),
+                    "test result: ok. 7 passed; 0 failed\ntest result: FAILED. 2 passed; 1 failed; 0 ignored",
+                    true,
+                ),
+                call("Read", json!({"file_path": "README.md"}), "...", false),
+            ],
+            duration: Some(Duration::from_secs(90)),
+            ..Default::default()
+        };
+
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_created, ["src/new.rs"]);
+        assert_eq!(digest.files_edited, ["src/lib.rs"]);
+        assert_eq!(digest.commands[0].exit_code, Some(101));
+        assert_eq!(
+            digest.tests,
+            Some(TestCounts {
+                passed: 9,
+                failed: 1,
+                run_failed: true
+            })
+        );
+        assert_eq!(digest.elapsed, Some(Duration::from_secs(90)));
+        assert_eq!(
+            digest.describe("Claude Code").unwrap(),
+            "Claude Code created 1 file and edited 1 file; ran the tests, 1 failed; saw 1 command fail"
+        );
+        assert_eq!(TaskDigest::default().describe("Claude Code"), None);
+    }
+
+    #[test]
+    fn test_digest_counts_last_run_of_each_test_command() {
+        let turn = LastTurn {
+            tool_calls: vec![
+                call(
+                    "Bash",
+                    json!({"command": "cargo test"}),
+                    "Exit code 101\ntest result: FAILED. 11 passed; 1 failed; 0 ignored",
+                    true,
+                ),
+                call(
+                    "Bash",
+                    json!({"command": "npm test"}),
+                    "Tests:       4 passed, 4 total",
+                    false,
+                ),
+                call("Edit", json!({"file_path": "src/lib.rs"}), "", false),
+                call(
+                    "Bash",
+                    json!({"command": "cargo test"}),
+                    "test result: ok. 12 passed; 0 failed; 0 ignored",
+                    false,
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(
+            digest.tests,
+            Some(TestCounts {
+                passed: 16,
+                failed: 0,
+                run_failed: false
+            })
+        );
+        assert_eq!(
+            digest.describe("Claude Code").unwrap(),
+            "Claude Code edited 1 file; ran the tests, all 16 passed"
+        );
+    }
+
+    #[test]
+    fn test_count_tests_across_runners() {
+        assert_eq!(
+            count_tests("===== 1 failed, 5 passed in 0.12s ====="),
+            (5, 1)
+        );
+        assert_eq!(
+            count_tests("Test Suites: 1 failed, 2 passed, 3 total\nTests:       1 failed, 11 passed, 12 total"),
+            (11, 1)
+        );
+        assert_eq!(count_tests("  12 passing (30ms)\n  2 failing"), (12, 2));
+        assert_eq!(count_tests("Compiling 3 crates"), (0, 0));
+
+        let run = |command: &str| CommandRun {
+            command: command.to_string(),
+            exit_code: Some(0),
+        };
+        assert!(run("npm run test:unit").is_test());
+        assert!(run("./node_modules/.bin/jest --ci").is_test());
+        assert!(!run("npm install left-pad@latest").is_test());
+    }
+}
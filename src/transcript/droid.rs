@@ -0,0 +1,147 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use super::format::{file_call, patch_calls, tool_call};
+use super::model::lenient_timestamp;
+use super::turn::TurnBuilder;
+use super::{read_tail, Content, ContentBlock, LastTurn, ToolCall};
+
+/// A line of a Droid session file; only messages matter here.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Line {
+    Message(MessageLine),
+    /// `session_start` and other bookkeeping lines
+    #[serde(other)]
+    Other,
+}
+
+impl Line {
+    fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim()).ok()
+    }
+
+    /// A user message with text and no tool results.
+    fn is_prompt(&self) -> bool {
+        let Line::Message(line) = self else {
+            return false;
+        };
+        line.message.role == "user"
+            && match &line.message.content {
+                Content::Text(_) => true,
+                Content::Blocks(blocks) => {
+                    blocks
+                        .iter()
+                        .any(|block| matches!(block, ContentBlock::Text { .. }))
+                        && !blocks
+                            .iter()
+                            .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+                }
+            }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageLine {
+    #[serde(default, deserialize_with = "lenient_timestamp")]
+    timestamp: Option<OffsetDateTime>,
+    message: DroidMessage,
+}
+
+/// Messages use Anthropic's content blocks, like Claude Code's.
+#[derive(Debug, Deserialize)]
+struct DroidMessage {
+    role: String,
+    #[serde(default)]
+    content: Content,
+}
+
+/// The last turn of a Droid session file.
+pub(super) fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let lines = read_tail(transcript_path, 1, Line::parse, Line::is_prompt)?;
+
+    let mut builder = TurnBuilder::default();
+    for line in lines {
+        let is_prompt = line.is_prompt();
+        let Line::Message(line) = line else {
+            continue;
+        };
+        if is_prompt {
+            builder.prompt(Some(line.message.content.text()), line.timestamp);
+            continue;
+        }
+        builder.seen(line.timestamp);
+
+        if let Content::Blocks(blocks) = &line.message.content {
+            for block in blocks {
+                match block {
+                    ContentBlock::ToolUse { id, name, input } => {
+                        builder.tool_calls(Some(id), droid_calls(name, input));
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                    } => {
+                        let output = content.as_ref().map(|c| c.text()).unwrap_or_default();
+                        builder.tool_result(tool_use_id, output, *is_error);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if line.message.role == "assistant" {
+            builder.text(&line.message.content.text());
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Droid's tools under Claude Code's names where they differ.
+fn droid_calls(name: &str, input: &serde_json::Value) -> Vec<ToolCall> {
+    match name {
+        "Execute" => vec![tool_call("Bash", input.clone())],
+        "Create" => match input["file_path"].as_str() {
+            Some(path) => vec![file_call(path, true)],
+            None => vec![tool_call("Write", input.clone())],
+        },
+        "ApplyPatch" => patch_calls(
+            input["input"]
+                .as_str()
+                .or_else(|| input["patch"].as_str())
+                .unwrap_or_default(),
+        ),
+        _ => vec![tool_call(name, input.clone())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TaskDigest;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_session_turn() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let lines = [
+            r#"{"type":"session_start","id":"s1","title":"Docs"}"#,
+            r#"{"type":"message","id":"m1","timestamp":"2025-10-01T09:00:00Z","message":{"role":"user","content":[{"type":"text","text":"Write a changelog"}]}}"#,
+            r#"{"type":"message","id":"m2","timestamp":"2025-10-01T09:00:20Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Create","input":{"file_path":"CHANGELOG.md","content":"Changes"}},{"type":"tool_use","id":"t2","name":"Execute","input":{"command":"npm test"}}]}}"#,
+            r#"{"type":"message","id":"m3","timestamp":"2025-10-01T09:00:40Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"Created"},{"type":"tool_result","tool_use_id":"t2","content":"12 passing"},{"type":"text","text":"<system-reminder>todo list</system-reminder>"}]}}"#,
+            r#"{"type":"message","id":"m4","timestamp":"2025-10-01T09:01:00Z","message":{"role":"assistant","content":[{"type":"text","text":"Added CHANGELOG.md."}]}}"#,
+        ];
+        writeln!(file, "{}", lines.join("\n")).unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(turn.request.as_deref(), Some("Write a changelog"));
+        assert_eq!(turn.final_text.as_deref(), Some("Added CHANGELOG.md."));
+        assert_eq!(turn.duration.unwrap().as_secs(), 60);
+
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_created, ["CHANGELOG.md"]);
+        assert_eq!(digest.tests.unwrap().passed, 12);
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::{aider, codex, droid, gemini, opencode, read_last_turns, LastTurn, ToolCall};
+
+/// Bytes read from the start of a transcript to detect its format.
+const HEAD_BYTES: u64 = 64 * 1024;
+
+/// `type`s of the first line of a Codex CLI rollout file.
+const CODEX_LINE_TYPES: [&str; 5] = [
+    "session_meta",
+    "response_item",
+    "event_msg",
+    "turn_context",
+    "compacted",
+];
+
+/// The coding agent that wrote a transcript, detected from its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// Claude Code JSONL, also assumed when nothing else matches
+    ClaudeCode,
+    /// Codex CLI rollout JSONL, `~/.codex/sessions/**/rollout-*.jsonl`
+    Codex,
+    /// Factory Droid session JSONL, `~/.factory/sessions/*.jsonl`
+    Droid,
+    /// Gemini CLI chat JSON, `~/.gemini/tmp/<project>/chats/session-*.json`
+    Gemini,
+    /// OpenCode session JSON written by `opencode export`
+    OpenCode,
+    /// Aider's Markdown `.aider.chat.history.md`
+    Aider,
+}
+
+impl TranscriptFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranscriptFormat::ClaudeCode => "Claude Code",
+            TranscriptFormat::Codex => "Codex CLI",
+            TranscriptFormat::Droid => "Droid",
+            TranscriptFormat::Gemini => "Gemini CLI",
+            TranscriptFormat::OpenCode => "OpenCode",
+            TranscriptFormat::Aider => "Aider",
+        }
+    }
+
+    /// Detect the format from the start of the file.
+    pub fn detect(transcript_path: &Path) -> Result<Self> {
+        let file = File::open(transcript_path)
+            .with_context(|| format!("Failed to open transcript file: {transcript_path:?}"))?;
+        let mut head = Vec::new();
+        file.take(HEAD_BYTES)
+            .read_to_end(&mut head)
+            .context("Failed to read transcript file")?;
+        Ok(Self::from_head(&String::from_utf8_lossy(&head)))
+    }
+
+    /// Detect the format from the first bytes of a transcript, whose last
+    /// line may be cut off.
+    pub fn from_head(head: &str) -> Self {
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        if !head.starts_with(['{', '[']) {
+            let aider = head.starts_with("# aider chat started")
+                || head.lines().any(|line| line.starts_with("#### "));
+            return if aider {
+                TranscriptFormat::Aider
+            } else {
+                TranscriptFormat::ClaudeCode
+            };
+        }
+
+        let first_line = head.lines().next().unwrap_or_default().trim();
+        let first = serde_json::from_str::<Value>(first_line).ok();
+        // A single pretty-printed or compact JSON document
+        let document = matches!(first_line, "{" | "[")
+            || first_line.starts_with("{\"sessionId\"")
+            || first
+                .as_ref()
+                .is_some_and(|value| value.is_array() || value.get("messages").is_some());
+        if document {
+            // Told apart by their top-level keys, not by text that tool
+            // input or output could contain
+            let keys = object_keys(head, 1);
+            return if keys.contains(&"info") {
+                TranscriptFormat::OpenCode
+            } else if keys.contains(&"projectHash") || keys.contains(&"sessionId") {
+                TranscriptFormat::Gemini
+            } else if head.starts_with('[') && object_keys(head, 2).contains(&"type") {
+                // Older Gemini CLI versions saved the messages array alone
+                TranscriptFormat::Gemini
+            } else {
+                TranscriptFormat::ClaudeCode
+            };
+        }
+
+        let Some(first) = first else {
+            // A first line longer than the head, e.g. Codex session metadata
+            return match first_line_type(first_line) {
+                Some(kind) if CODEX_LINE_TYPES.contains(&kind) => TranscriptFormat::Codex,
+                _ => TranscriptFormat::ClaudeCode,
+            };
+        };
+        match first["type"].as_str() {
+            Some(kind) if CODEX_LINE_TYPES.contains(&kind) => TranscriptFormat::Codex,
+            // Older rollouts start with bare session metadata, then bare items
+            None if first.get("instructions").is_some() => TranscriptFormat::Codex,
+            Some("message") if first.get("role").is_some() => TranscriptFormat::Codex,
+            Some("session_start" | "message") => TranscriptFormat::Droid,
+            _ => TranscriptFormat::ClaudeCode,
+        }
+    }
+
+    /// The request, tool calls and final response since the most recent
+    /// prompt, the same for every format.
+    pub fn read_last_turn(&self, transcript_path: &Path) -> Result<LastTurn> {
+        match self {
+            TranscriptFormat::ClaudeCode => {
+                let entries = read_last_turns(transcript_path, 1)?;
+                Ok(LastTurn::from_entries(&entries))
+            }
+            TranscriptFormat::Codex => codex::read_last_turn(transcript_path),
+            TranscriptFormat::Droid => droid::read_last_turn(transcript_path),
+            TranscriptFormat::Gemini => gemini::read_last_turn(transcript_path),
+            TranscriptFormat::OpenCode => opencode::read_last_turn(transcript_path),
+            TranscriptFormat::Aider => aider::read_last_turn(transcript_path),
+        }
+    }
+}
+
+impl fmt::Display for TranscriptFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Keys of the first object opened at `depth` (1 for the document itself)
+/// in JSON that may be cut off, read without parsing the rest.
+fn object_keys(json: &str, depth: usize) -> Vec<&str> {
+    let bytes = json.as_bytes();
+    let mut keys = Vec::new();
+    let mut level = 0;
+    let mut in_object = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != b'"' {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                if end >= bytes.len() {
+                    break;
+                }
+                if in_object && level == depth && json[end + 1..].trim_start().starts_with(':') {
+                    keys.push(&json[start..end]);
+                }
+                i = end;
+            }
+            open @ (b'{' | b'[') => {
+                level += 1;
+                in_object |= open == b'{' && level == depth;
+            }
+            b'}' | b']' => {
+                if in_object && level == depth {
+                    break;
+                }
+                level = level.saturating_sub(1);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    keys
+}
+
+/// The top-level `"type":"..."` of a JSON line too long to parse.
+fn first_line_type(line: &str) -> Option<&str> {
+    let start = line.find("\"type\":\"")? + "\"type\":\"".len();
+    let end = line[start..].find('"')?;
+    Some(&line[start..start + end])
+}
+
+// Other agents' tool calls are mapped onto Claude Code's tool names and
+// result texts, so the digest and outcome read every format the same way.
+
+pub(super) fn tool_call(name: &str, input: Value) -> ToolCall {
+    ToolCall {
+        name: name.to_string(),
+        input,
+        output: None,
+        is_error: false,
+    }
+}
+
+pub(super) fn shell_call(command: &str) -> ToolCall {
+    tool_call("Bash", json!({ "command": command }))
+}
+
+/// `Write` for a new file, with the result Claude Code gives it; `Edit`
+/// otherwise.
+pub(super) fn file_call(path: &str, created: bool) -> ToolCall {
+    if created {
+        ToolCall {
+            output: Some(format!("File created successfully at: {path}")),
+            ..tool_call("Write", json!({ "file_path": path }))
+        }
+    } else {
+        tool_call("Edit", json!({ "file_path": path }))
+    }
+}
+
+/// One call per file of an `apply_patch` style patch.
+pub(super) fn patch_calls(patch: &str) -> Vec<ToolCall> {
+    patch
+        .lines()
+        .filter_map(|line| {
+            if let Some(path) = line.strip_prefix("*** Add File: ") {
+                Some(file_call(path.trim(), true))
+            } else {
+                line.strip_prefix("*** Update File: ")
+                    .or_else(|| line.strip_prefix("*** Delete File: "))
+                    .map(|path| file_call(path.trim(), false))
+            }
+        })
+        .collect()
+}
+
+/// Shell output and whether it failed, starting with "Exit code N" like
+/// Claude Code's results when the exit code isn't 0.
+pub(super) fn shell_result(output: &str, exit_code: Option<i64>) -> (String, bool) {
+    match exit_code {
+        Some(code) if code != 0 => (format!("Exit code {code}\n{output}"), true),
+        _ => (output.to_string(), false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_head() {
+        let detect = TranscriptFormat::from_head;
+        assert_eq!(
+            detect(r#"{"parentUuid":null,"type":"user","message":{"role":"user","content":"hi"}}"#),
+            TranscriptFormat::ClaudeCode
+        );
+        assert_eq!(
+            detect(
+                r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"x","instructions":"#
+            ),
+            TranscriptFormat::Codex
+        );
+        assert_eq!(
+            detect("{\"id\":\"x\",\"timestamp\":\"2025-05-01\",\"instructions\":null}\n{\"record_type\":\"state\"}"),
+            TranscriptFormat::Codex
+        );
+        assert_eq!(
+            detect(r#"{"type":"session_start","id":"s1","title":"Fix tests"}"#),
+            TranscriptFormat::Droid
+        );
+        assert_eq!(
+            detect("{\n  \"sessionId\": \"s1\",\n  \"projectHash\": \"abc\",\n  \"messages\": ["),
+            TranscriptFormat::Gemini
+        );
+        assert_eq!(
+            detect("{\n  \"info\": {\n    \"id\": \"ses_1\"\n  },\n  \"messages\": [\n    {\n      \"parts\": ["),
+            TranscriptFormat::OpenCode
+        );
+        assert_eq!(
+            detect("\n# aider chat started at 2025-06-01 10:00:00\n\n> Aider v0.86.0\n"),
+            TranscriptFormat::Aider
+        );
+        assert_eq!(detect(""), TranscriptFormat::ClaudeCode);
+    }
+
+    #[test]
+    fn test_detect_ignores_quoted_format_names() {
+        let detect = TranscriptFormat::from_head;
+        assert_eq!(
+            detect(concat!(
+                r#"{"type":"user","message":{"role":"user","content":"Compare \"gemini\" and \"parts\" in the exports"}}"#,
+                "\n",
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Grep","input":{"pattern":"\"projectHash\""}}]}}"#
+            )),
+            TranscriptFormat::ClaudeCode
+        );
+        assert_eq!(
+            detect("{\n  \"info\": {\"title\": \"Read \\\"projectHash\\\" from gemini\"},\n  \"messages\": ["),
+            TranscriptFormat::OpenCode
+        );
+        assert_eq!(
+            detect("{\n  \"messages\": [{\"content\": \"the \\\"gemini\\\" model\"}]"),
+            TranscriptFormat::ClaudeCode
+        );
+        assert_eq!(
+            detect("[\n  {\"id\": \"1\", \"type\": \"user\", \"content\": \"hi\"}"),
+            TranscriptFormat::Gemini
+        );
+        assert_eq!(
+            object_keys(r#"{"a": {"b": 1}, "c": "d:", "e\"f": ["g"]}"#, 1),
+            ["a", "c", "e\\\"f"]
+        );
+    }
+
+    #[test]
+    fn test_patch_calls() {
+        let calls = patch_calls(
+            "*** Begin Patch\n*** Add File: src/new.rs\n+fn main() {}\n*** Update File: src/lib.rs\n@@\n-a\n+b\n*** End Patch",
+        );
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "Write");
+        assert_eq!(calls[1].target(), Some("src/lib.rs"));
+        assert_eq!(
+            shell_result("boom", Some(2)),
+            ("Exit code 2\nboom".to_string(), true)
+        );
+    }
+}
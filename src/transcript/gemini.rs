@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use super::format::{file_call, shell_call, shell_result, tool_call};
+use super::model::lenient_timestamp;
+use super::turn::TurnBuilder;
+use super::{LastTurn, ToolCall};
+
+/// A Gemini CLI chat session, saved as one JSON document.
+#[derive(Debug, Deserialize)]
+struct Session {
+    #[serde(default)]
+    messages: Vec<GeminiMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiMessage {
+    /// `user`, `gemini`, or `info`, `warning` and `error` notices
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, deserialize_with = "lenient_timestamp")]
+    timestamp: Option<OffsetDateTime>,
+    /// A string, or parts with `text`
+    #[serde(default)]
+    content: Value,
+    #[serde(default)]
+    tool_calls: Vec<GeminiToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiToolCall {
+    #[serde(default)]
+    id: String,
+    name: String,
+    #[serde(default)]
+    args: Value,
+    /// `functionResponse` parts sent back to the model
+    #[serde(default)]
+    result: Value,
+    /// `success`, `error` or `cancelled`
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    result_display: Value,
+}
+
+impl GeminiToolCall {
+    fn output(&self) -> String {
+        let responses = self.result.as_array().into_iter().flatten();
+        let output = responses
+            .filter_map(|part| {
+                let response = &part["functionResponse"]["response"];
+                response["output"].as_str().or(response["error"].as_str())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if output.is_empty() {
+            self.result_display.as_str().unwrap_or_default().to_string()
+        } else {
+            output
+        }
+    }
+
+    /// The call under Claude Code's tool names, and its result.
+    fn to_call(&self) -> (ToolCall, String, bool) {
+        let output = self.output();
+        if self.status == "cancelled" {
+            let call = tool_call(&self.name, self.args.clone());
+            return (call, "Cancelled by the user".to_string(), true);
+        }
+        let is_error = self.status == "error";
+        let path = self.args["file_path"]
+            .as_str()
+            .or(self.args["absolute_path"].as_str());
+        match (self.name.as_str(), path) {
+            ("run_shell_command", _) => {
+                let call = shell_call(self.args["command"].as_str().unwrap_or_default());
+                let (output, failed) = shell_result(&output, exit_code(&output));
+                (call, output, is_error || failed)
+            }
+            // "Successfully created and wrote to new file: ..." for new files
+            ("write_file", Some(path)) => {
+                let created = !is_error && output.contains("created");
+                (file_call(path, created), output, is_error)
+            }
+            ("replace" | "edit", Some(path)) => (file_call(path, false), output, is_error),
+            ("read_file", Some(path)) => {
+                let call = tool_call("Read", json!({ "file_path": path }));
+                (call, output, is_error)
+            }
+            _ => (tool_call(&self.name, self.args.clone()), output, is_error),
+        }
+    }
+}
+
+/// The last turn of a Gemini CLI chat session file.
+pub(super) fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let contents = fs::read_to_string(transcript_path)
+        .with_context(|| format!("Failed to read transcript file: {transcript_path:?}"))?;
+    // Older versions saved the messages array alone
+    let messages = match serde_json::from_str::<Session>(&contents) {
+        Ok(session) => session.messages,
+        Err(_) => serde_json::from_str(&contents).context("Failed to parse Gemini CLI session")?,
+    };
+
+    let mut builder = TurnBuilder::default();
+    for message in &messages {
+        match message.kind.as_str() {
+            "user" => builder.prompt(Some(content_text(&message.content)), message.timestamp),
+            "gemini" => {
+                builder.seen(message.timestamp);
+                builder.text(&content_text(&message.content));
+                for call in &message.tool_calls {
+                    let (tool_call, output, is_error) = call.to_call();
+                    builder.tool_call(Some(&call.id), tool_call);
+                    builder.tool_result(&call.id, output, is_error);
+                }
+            }
+            _ => builder.seen(message.timestamp),
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+/// The "Exit Code: N" line of `run_shell_command` output.
+fn exit_code(output: &str) -> Option<i64> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Exit Code:"))
+        .and_then(|code| code.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::{Outcome, TaskDigest};
+
+    #[test]
+    fn test_read_chat_session() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            r#"{
+  "sessionId": "s1",
+  "projectHash": "abc",
+  "messages": [
+    {"id": "1", "timestamp": "2025-11-01T12:00:00Z", "type": "user", "content": "Fix the build"},
+    {"id": "2", "timestamp": "2025-11-01T12:00:30Z", "type": "gemini", "content": "Updating the config.",
+     "toolCalls": [
+       {"id": "c1", "name": "replace", "args": {"file_path": "/repo/build.gradle"}, "status": "success",
+        "result": [{"functionResponse": {"id": "c1", "name": "replace", "response": {"output": "Successfully modified file"}}}]},
+       {"id": "c2", "name": "run_shell_command", "args": {"command": "./gradlew build"}, "status": "success",
+        "result": [{"functionResponse": {"id": "c2", "name": "run_shell_command", "response": {"output": "Command: ./gradlew build\nStdout: BUILD FAILED\nExit Code: 1"}}}]}
+     ]},
+    {"id": "3", "timestamp": "2025-11-01T12:02:00Z", "type": "gemini", "content": "The build still fails on a missing dependency."}
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(turn.request.as_deref(), Some("Fix the build"));
+        assert_eq!(turn.duration.unwrap().as_secs(), 120);
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_edited, ["/repo/build.gradle"]);
+        assert_eq!(digest.commands[0].exit_code, Some(1));
+        assert_eq!(Outcome::classify(&turn, &digest), Outcome::Failure);
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tracing::{debug, info};
+
+mod aider;
+mod codex;
+mod digest;
+mod droid;
+mod format;
+mod gemini;
+mod model;
+mod opencode;
+mod outcome;
+mod reverse;
+mod turn;
+
+pub use digest::{CommandRun, TaskDigest, TestCounts};
+pub use format::TranscriptFormat;
+pub use model::{Content, ContentBlock, Entry, EntryMeta, Message, MessageEntry, SystemEntry};
+pub use outcome::Outcome;
+pub use reverse::ReverseLines;
+pub use turn::{LastTurn, ToolCall};
+
+/// Every entry of a transcript, skipping blank and malformed lines.
+pub fn read_entries(transcript_path: &Path) -> Result<Vec<Entry>> {
+    let file = fs::File::open(transcript_path)
+        .with_context(|| format!("Failed to open transcript file: {transcript_path:?}"))?;
+
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.context("Failed to read line")?;
+        match Entry::parse(&line) {
+            Some(entry) => entries.push(entry),
+            None if line.trim().is_empty() => {}
+            None => skipped += 1,
+        }
+    }
+    debug!(
+        "Read {} transcript entries ({} unreadable lines skipped)",
+        entries.len(),
+        skipped
+    );
+    Ok(entries)
+}
+
+/// Entries from the end of the transcript backwards, reading only as much
+/// of the file as is consumed.
+pub fn read_entries_rev(transcript_path: &Path) -> Result<impl Iterator<Item = Result<Entry>>> {
+    let file = fs::File::open(transcript_path)
+        .with_context(|| format!("Failed to open transcript file: {transcript_path:?}"))?;
+    let lines = ReverseLines::new(file).context("Failed to seek transcript file")?;
+    Ok(lines.filter_map(|line| match line {
+        Ok(line) => Entry::parse(&line).map(Ok),
+        Err(e) => Some(Err(anyhow::Error::new(e).context("Failed to read line"))),
+    }))
+}
+
+/// The entries of the last `turns` turns in file order, each starting at a
+/// prompt. Read from the end, so the cost doesn't grow with the transcript.
+pub fn read_last_turns(transcript_path: &Path, turns: usize) -> Result<Vec<Entry>> {
+    read_tail(transcript_path, turns, Entry::parse, Entry::is_prompt)
+}
+
+/// Lines of a line-based transcript parsed from the end back to the
+/// `turns`th prompt, in file order; lines `parse` rejects are skipped.
+fn read_tail<T>(
+    transcript_path: &Path,
+    turns: usize,
+    parse: impl Fn(&str) -> Option<T>,
+    is_prompt: impl Fn(&T) -> bool,
+) -> Result<Vec<T>> {
+    let file = fs::File::open(transcript_path)
+        .with_context(|| format!("Failed to open transcript file: {transcript_path:?}"))?;
+    let mut items = Vec::new();
+    let mut prompts = 0;
+    if turns > 0 {
+        for line in ReverseLines::new(file).context("Failed to seek transcript file")? {
+            let Some(item) = parse(&line.context("Failed to read line")?) else {
+                continue;
+            };
+            let prompt = is_prompt(&item);
+            items.push(item);
+            if prompt {
+                prompts += 1;
+                if prompts == turns {
+                    break;
+                }
+            }
+        }
+    }
+    items.reverse();
+    Ok(items)
+}
+
+/// The request, tool calls and final response since the most recent prompt,
+/// from a transcript of any supported agent.
+pub fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let format = TranscriptFormat::detect(transcript_path)?;
+    let turn = format.read_last_turn(transcript_path)?;
+    debug!(
+        "Last {} turn: {} tool calls, final response: {}",
+        format,
+        turn.tool_calls.len(),
+        turn.final_text.is_some()
+    );
+    Ok(turn)
+}
+
+pub fn extract_last_assistant_message(transcript_path: &Path) -> Result<String> {
+    info!("Reading JSONL transcript from: {:?}", transcript_path);
+
+    let mut last_assistant_message = None;
+    for entry in read_entries_rev(transcript_path)? {
+        let entry = entry?;
+        if entry.is_sidechain() {
+            continue;
+        }
+        let Some(assistant) = entry.as_assistant() else {
+            continue;
+        };
+        let text = assistant.message.text();
+        if !text.trim().is_empty() {
+            last_assistant_message = Some(text);
+            break;
+        }
+    }
+
+    match last_assistant_message {
+        Some(message) => {
+            info!(
+                "Found last assistant message with {} characters",
+                message.len()
+            );
+            debug!(
+                "Last assistant message preview: {}...",
+                &message.chars().take(100).collect::<String>()
+            );
+            Ok(message)
+        }
+        None => Err(anyhow::anyhow!("No assistant message found in transcript")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::Duration;
+
+    #[test]
+    fn test_last_assistant_message_skips_tools_and_sidechains() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"fix it"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Fixed the parser."}]}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"role":"assistant","content":"Subagent report"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"summary","summary":"Parser fix"}"#,
+        ];
+        writeln!(file, "{}", lines.join("\n")).unwrap();
+
+        assert_eq!(
+            extract_last_assistant_message(file.path()).unwrap(),
+            "Fixed the parser."
+        );
+    }
+
+    #[test]
+    fn test_read_last_turns() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let lines = [
+            r#"{"type":"user","timestamp":"2025-06-01T10:00:00Z","message":{"role":"user","content":"first task"}}"#,
+            r#"{"type":"assistant","timestamp":"2025-06-01T10:01:00Z","message":{"role":"assistant","content":[{"type":"tool_use","name":"Write","input":{"file_path":"/repo/old.rs"}}]}}"#,
+            r#"{"type":"user","timestamp":"2025-06-01T11:00:00Z","message":{"role":"user","content":[{"type":"text","text":"second task"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-06-01T11:00:30Z","message":{"role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/src/main.rs"}},{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","timestamp":"2025-06-01T11:01:00Z","message":{"role":"user","content":[{"type":"tool_result","content":"ok"},{"type":"text","text":"<system-reminder>The todo list changed</system-reminder>"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-06-01T11:02:05Z","message":{"role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/src/main.rs"}},{"type":"text","text":"Done."}]}}"#,
+        ];
+        writeln!(file, "{}", lines.join("\n")).unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(turn.request.as_deref(), Some("second task"));
+        assert_eq!(turn.duration, Some(Duration::from_secs(125)));
+        assert_eq!(turn.tool_calls.len(), 3);
+
+        let turns = read_last_turns(file.path(), 2).unwrap();
+        assert_eq!(turns.len(), 6);
+        assert_eq!(read_last_turns(file.path(), 1).unwrap().len(), 4);
+        assert!(read_last_turns(file.path(), 1).unwrap()[0].is_prompt());
+        assert_eq!(read_last_turns(file.path(), 5).unwrap().len(), 6);
+    }
+}
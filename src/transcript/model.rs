@@ -0,0 +1,288 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// One line of a Claude Code JSONL transcript.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Entry {
+    User(MessageEntry),
+    Assistant(MessageEntry),
+    System(SystemEntry),
+    /// Summaries, file history snapshots and other bookkeeping lines
+    #[serde(other)]
+    Other,
+}
+
+impl Entry {
+    /// Parse one transcript line; `None` for blank or malformed lines.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        serde_json::from_str(line).ok()
+    }
+
+    pub fn meta(&self) -> Option<&EntryMeta> {
+        match self {
+            Entry::User(entry) | Entry::Assistant(entry) => Some(&entry.meta),
+            Entry::System(entry) => Some(&entry.meta),
+            Entry::Other => None,
+        }
+    }
+
+    pub fn timestamp(&self) -> Option<OffsetDateTime> {
+        self.meta().and_then(|meta| meta.timestamp)
+    }
+
+    /// Whether the entry belongs to a subagent rather than the main thread.
+    pub fn is_sidechain(&self) -> bool {
+        self.meta().is_some_and(|meta| meta.is_sidechain)
+    }
+
+    pub fn as_assistant(&self) -> Option<&MessageEntry> {
+        match self {
+            Entry::Assistant(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Text a person typed on the main thread, as opposed to a tool result
+    /// or a subagent's instructions. Text sent along with tool results,
+    /// such as a system reminder, isn't a prompt either.
+    pub fn is_prompt(&self) -> bool {
+        match self {
+            Entry::User(entry) if !entry.meta.is_sidechain => match &entry.message.content {
+                Content::Text(_) => true,
+                Content::Blocks(blocks) => {
+                    blocks
+                        .iter()
+                        .any(|block| matches!(block, ContentBlock::Text { .. }))
+                        && !blocks
+                            .iter()
+                            .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+                }
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Fields shared by every conversation entry.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryMeta {
+    pub uuid: Option<String>,
+    pub parent_uuid: Option<String>,
+    /// `None` when missing or not RFC 3339
+    #[serde(default, deserialize_with = "lenient_timestamp")]
+    pub timestamp: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub is_sidechain: bool,
+    pub session_id: Option<String>,
+    pub cwd: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MessageEntry {
+    #[serde(flatten)]
+    pub meta: EntryMeta,
+    pub message: Message,
+}
+
+/// Hook output, compaction notices and other lines Claude Code writes itself.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SystemEntry {
+    #[serde(flatten)]
+    pub meta: EntryMeta,
+    pub subtype: Option<String>,
+    pub level: Option<String>,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Message {
+    #[serde(default)]
+    pub content: Content,
+    pub model: Option<String>,
+    pub stop_reason: Option<String>,
+}
+
+impl Message {
+    /// The text blocks joined by blank lines, or the plain string content.
+    pub fn text(&self) -> String {
+        self.content.text()
+    }
+
+    pub fn blocks(&self) -> &[ContentBlock] {
+        match &self.content {
+            Content::Text(_) => &[],
+            Content::Blocks(blocks) => blocks,
+        }
+    }
+
+    /// `(name, input)` of every tool call in the message.
+    pub fn tool_uses(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.blocks().iter().filter_map(|block| match block {
+            ContentBlock::ToolUse { name, input, .. } => Some((name.as_str(), input)),
+            _ => None,
+        })
+    }
+}
+
+/// Message content, which Claude Code writes either as a plain string or as
+/// an array of blocks.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Content {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Content::Blocks(Vec::new())
+    }
+}
+
+impl Content {
+    pub fn text(&self) -> String {
+        match self {
+            Content::Text(text) => text.clone(),
+            Content::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+    },
+    ToolUse {
+        #[serde(default)]
+        id: String,
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    ToolResult {
+        #[serde(default)]
+        tool_use_id: String,
+        /// Output of the tool, itself a string or blocks
+        #[serde(default)]
+        content: Option<Content>,
+        #[serde(default)]
+        is_error: bool,
+    },
+    /// Images, redacted thinking and block types added later
+    #[serde(other)]
+    Other,
+}
+
+pub(super) fn lenient_timestamp<'de, D>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let timestamp = Option::<String>::deserialize(deserializer)?;
+    Ok(timestamp.and_then(|timestamp| OffsetDateTime::parse(&timestamp, &Rfc3339).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message_entries() {
+        let prompt = Entry::parse(
+            r#"{"type":"user","uuid":"u1","parentUuid":null,"timestamp":"2025-06-01T10:00:00.123Z","isSidechain":false,"sessionId":"s1","message":{"role":"user","content":"fix the tests"}}"#,
+        )
+        .unwrap();
+        assert!(prompt.is_prompt());
+        let meta = prompt.meta().unwrap();
+        assert_eq!(meta.uuid.as_deref(), Some("u1"));
+        assert_eq!(meta.parent_uuid, None);
+        assert_eq!(prompt.timestamp().unwrap().unix_timestamp(), 1748772000);
+
+        let assistant = Entry::parse(
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","timestamp":"not a time","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"thinking","thinking":"Look first.","signature":"x"},{"type":"text","text":"Running them."},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}},{"type":"image","source":{}}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(assistant.timestamp(), None);
+        let message = &assistant.as_assistant().unwrap().message;
+        assert_eq!(message.text(), "Running them.");
+        assert_eq!(
+            message.blocks()[0],
+            ContentBlock::Thinking {
+                thinking: "Look first.".into()
+            }
+        );
+        assert_eq!(message.blocks()[3], ContentBlock::Other);
+        let (name, input) = message.tool_uses().next().unwrap();
+        assert_eq!(
+            (name, input["command"].as_str()),
+            ("Bash", Some("cargo test"))
+        );
+
+        let result = Entry::parse(
+            r#"{"type":"user","isSidechain":true,"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":[{"type":"text","text":"1 failed"}]}]}}"#,
+        )
+        .unwrap();
+        assert!(result.is_sidechain());
+        assert!(!result.is_prompt());
+
+        let reminder = Entry::parse(
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"},{"type":"text","text":"<system-reminder>The todo list changed</system-reminder>"}]}}"#,
+        )
+        .unwrap();
+        assert!(!reminder.is_prompt());
+        let Entry::User(entry) = &result else {
+            panic!("expected a user entry");
+        };
+        assert_eq!(
+            entry.message.blocks()[0],
+            ContentBlock::ToolResult {
+                tool_use_id: "t1".into(),
+                content: Some(Content::Blocks(vec![ContentBlock::Text {
+                    text: "1 failed".into()
+                }])),
+                is_error: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_other_entries() {
+        let system = Entry::parse(
+            r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","level":"info","uuid":"s1"}"#,
+        )
+        .unwrap();
+        let Entry::System(system) = system else {
+            panic!("expected a system entry");
+        };
+        assert_eq!(system.content.as_deref(), Some("Conversation compacted"));
+        assert_eq!(system.meta.uuid.as_deref(), Some("s1"));
+
+        assert_eq!(
+            Entry::parse(r#"{"type":"summary","summary":"Fixing tests","leafUuid":"a1"}"#),
+            Some(Entry::Other)
+        );
+        assert_eq!(Entry::parse("  "), None);
+        assert_eq!(Entry::parse("{not json"), None);
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use super::format::{file_call, patch_calls, shell_call, shell_result, tool_call};
+use super::turn::TurnBuilder;
+use super::{LastTurn, ToolCall};
+
+/// A session as printed by `opencode export <session>`.
+#[derive(Debug, Deserialize)]
+struct Export {
+    #[serde(default)]
+    messages: Vec<ExportMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportMessage {
+    info: MessageInfo,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageInfo {
+    role: String,
+    #[serde(default)]
+    time: Times,
+}
+
+/// Unix times in milliseconds.
+#[derive(Debug, Default, Deserialize)]
+struct Times {
+    created: Option<i64>,
+    completed: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Part {
+    Text {
+        #[serde(default)]
+        text: String,
+        /// Added by OpenCode rather than typed or said
+        #[serde(default)]
+        synthetic: bool,
+    },
+    Tool {
+        tool: String,
+        #[serde(default, rename = "callID")]
+        call_id: String,
+        state: ToolState,
+    },
+    /// Reasoning, step markers, snapshots and part types added later
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolState {
+    /// `pending`, `running`, `completed` or `error`
+    status: String,
+    #[serde(default)]
+    input: Value,
+    output: Option<String>,
+    error: Option<String>,
+    #[serde(default)]
+    metadata: Value,
+}
+
+impl ExportMessage {
+    fn text(&self) -> String {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Text {
+                    text,
+                    synthetic: false,
+                } if !text.trim().is_empty() => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn timestamp(&self) -> Option<OffsetDateTime> {
+        let millis = self.info.time.completed.or(self.info.time.created)?;
+        OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+    }
+}
+
+/// The last turn of an exported OpenCode session.
+pub(super) fn read_last_turn(transcript_path: &Path) -> Result<LastTurn> {
+    let contents = fs::read_to_string(transcript_path)
+        .with_context(|| format!("Failed to read transcript file: {transcript_path:?}"))?;
+    let export: Export =
+        serde_json::from_str(&contents).context("Failed to parse OpenCode session")?;
+
+    let mut builder = TurnBuilder::default();
+    for message in &export.messages {
+        if message.info.role == "user" {
+            builder.prompt(Some(message.text()), message.timestamp());
+            continue;
+        }
+        builder.seen(message.timestamp());
+        for part in &message.parts {
+            if let Part::Tool {
+                tool,
+                call_id,
+                state,
+            } = part
+            {
+                let (calls, output, is_error) = tool_calls(tool, state);
+                builder.tool_calls(Some(call_id), calls);
+                if let Some(output) = output {
+                    builder.tool_result(call_id, output, is_error);
+                }
+            }
+        }
+        builder.text(&message.text());
+    }
+    Ok(builder.finish())
+}
+
+/// OpenCode's tools under Claude Code's names, with the result if the call
+/// finished.
+fn tool_calls(tool: &str, state: &ToolState) -> (Vec<ToolCall>, Option<String>, bool) {
+    let input = &state.input;
+    let path = input["filePath"].as_str();
+    let calls = match (tool, path) {
+        ("bash", _) => vec![shell_call(input["command"].as_str().unwrap_or_default())],
+        ("write", Some(path)) => {
+            let created = state.metadata["exists"] == Value::Bool(false);
+            vec![file_call(path, created)]
+        }
+        ("edit", Some(path)) => vec![file_call(path, false)],
+        ("read", Some(path)) => vec![tool_call("Read", json!({ "file_path": path }))],
+        ("patch", _) => patch_calls(input["patchText"].as_str().unwrap_or_default()),
+        _ => vec![tool_call(tool, input.clone())],
+    };
+
+    match state.status.as_str() {
+        "error" => (calls, state.error.clone(), true),
+        "completed" => {
+            let output = state.output.as_deref().unwrap_or_default();
+            let (output, failed) = match tool {
+                "bash" => shell_result(output, state.metadata["exit"].as_i64()),
+                _ => (output.to_string(), false),
+            };
+            (calls, Some(output), failed)
+        }
+        _ => (calls, None, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TaskDigest;
+
+    #[test]
+    fn test_read_exported_session() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            r#"{
+  "info": {"id": "ses_1", "title": "Rename"},
+  "messages": [
+    {"info": {"id": "msg_1", "role": "user", "time": {"created": 1760000000000}},
+     "parts": [{"type": "text", "text": "Rename the config module"}]},
+    {"info": {"id": "msg_2", "role": "assistant", "time": {"created": 1760000001000, "completed": 1760000045000}},
+     "parts": [
+       {"type": "step-start"},
+       {"type": "tool", "tool": "write", "callID": "c1",
+        "state": {"status": "completed", "input": {"filePath": "/repo/src/settings.ts"}, "output": "", "metadata": {"exists": false}}},
+       {"type": "tool", "tool": "bash", "callID": "c2",
+        "state": {"status": "completed", "input": {"command": "rm src/config.ts"}, "output": "", "metadata": {"exit": 0}}},
+       {"type": "tool", "tool": "bash", "callID": "c3",
+        "state": {"status": "error", "input": {"command": "git push"}, "error": "The user rejected permission to use this specific tool call."}},
+       {"type": "text", "text": "Renamed config to settings."}
+     ]}
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let turn = read_last_turn(file.path()).unwrap();
+        assert_eq!(turn.request.as_deref(), Some("Rename the config module"));
+        assert_eq!(turn.duration.unwrap().as_secs(), 45);
+        assert_eq!(
+            turn.final_text.as_deref(),
+            Some("Renamed config to settings.")
+        );
+        assert!(turn.tool_calls[2].is_error);
+        let digest = TaskDigest::from_turn(&turn);
+        assert_eq!(digest.files_created, ["/repo/src/settings.ts"]);
+        assert_eq!(digest.commands.len(), 2);
+    }
+}
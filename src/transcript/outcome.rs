@@ -0,0 +1,226 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::{LastTurn, TaskDigest};
+
+/// Tool result text meaning the user or a hook refused the call.
+const DENIAL_MARKERS: [&str; 5] = [
+    "doesn't want to proceed",
+    "has been denied",
+    "was blocked by",
+    "rejected permission",
+    "cancelled by the user",
+];
+
+/// Final response phrases meaning the agent can't go on by itself.
+const BLOCKED_PHRASES: [&str; 6] = [
+    "i'm blocked",
+    "i am blocked",
+    "unable to proceed",
+    "can't proceed",
+    "cannot proceed",
+    "need your approval",
+];
+
+/// Final response phrases admitting the task didn't work.
+const FAILURE_PHRASES: [&str; 5] = [
+    "still failing",
+    "still fails",
+    "couldn't fix",
+    "could not fix",
+    "failed to",
+];
+
+/// How a task ended, for choosing the tone, voice and earcon of its
+/// notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Success,
+    /// Failing tests or builds, or an error in the final tool call
+    Failure,
+    /// Waiting on a permission or something only the user can do
+    Blocked,
+    /// Ended by asking the user something
+    Question,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 4] = [
+        Outcome::Success,
+        Outcome::Failure,
+        Outcome::Blocked,
+        Outcome::Question,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Failure => "failure",
+            Outcome::Blocked => "blocked",
+            Outcome::Question => "question",
+        }
+    }
+
+    /// Heuristic classification of a finished turn, no LLM needed.
+    pub fn classify(turn: &LastTurn, digest: &TaskDigest) -> Self {
+        let last_call = turn.tool_calls.last();
+        let denied = last_call.is_some_and(|call| {
+            call.is_error
+                && call.output.as_deref().is_some_and(|output| {
+                    let output = output.to_lowercase();
+                    DENIAL_MARKERS.iter().any(|marker| output.contains(marker))
+                })
+        });
+        if denied {
+            return Outcome::Blocked;
+        }
+
+        let final_text = turn.final_text.as_deref().unwrap_or_default();
+        let last_line = final_text.trim().lines().last().unwrap_or_default();
+        if last_line.trim_end_matches(['*', '_', ')']).ends_with('?') {
+            return Outcome::Question;
+        }
+
+        let final_text = final_text.to_lowercase();
+        if BLOCKED_PHRASES
+            .iter()
+            .any(|phrase| final_text.contains(phrase))
+        {
+            return Outcome::Blocked;
+        }
+
+        let last_test_failed = digest
+            .commands
+            .iter()
+            .rev()
+            .find(|command| command.is_test())
+            .is_some_and(|command| command.failed());
+        if last_test_failed
+            || last_call.is_some_and(|call| call.is_error)
+            || FAILURE_PHRASES
+                .iter()
+                .any(|phrase| final_text.contains(phrase))
+        {
+            return Outcome::Failure;
+        }
+        Outcome::Success
+    }
+
+    /// Split a leading `[failure]`-style tag, which the summarizer adds when
+    /// `classify_outcome` is on, from the text to speak.
+    pub fn strip_tag(text: &str) -> (Option<Self>, &str) {
+        let trimmed = text.trim_start();
+        let tagged = trimmed.strip_prefix('[').and_then(|rest| {
+            let (tag, after) = rest.split_once(']')?;
+            Some((tag.parse().ok()?, after.trim_start()))
+        });
+        match tagged {
+            Some((outcome, rest)) => (Some(outcome), rest),
+            None => (None, text),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+        Outcome::ALL
+            .into_iter()
+            .find(|outcome| outcome.as_str() == value)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown outcome '{}' (expected success, failure, blocked or question)",
+                    value
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::ToolCall;
+    use serde_json::json;
+
+    fn turn(calls: &[(&str, &str, bool)], final_text: &str) -> Outcome {
+        let turn = LastTurn {
+            tool_calls: calls
+                .iter()
+                .map(|(command, output, is_error)| ToolCall {
+                    name: "Bash".to_string(),
+                    input: json!({ "command": command }),
+                    output: Some(output.to_string()),
+                    is_error: *is_error,
+                })
+                .collect(),
+            final_text: Some(final_text.to_string()),
+            ..Default::default()
+        };
+        Outcome::classify(&turn, &TaskDigest::from_turn(&turn))
+    }
+
+    #[test]
+    fn test_classify_final_turn() {
+        assert_eq!(
+            turn(
+                &[("cargo test", "test result: ok. 3 passed", false)],
+                "Done."
+            ),
+            Outcome::Success
+        );
+        // A failing run that was fixed afterwards is still a success
+        assert_eq!(
+            turn(
+                &[
+                    ("cargo test", "Exit code 101\n1 failed", true),
+                    ("cargo test", "test result: ok. 3 passed", false)
+                ],
+                "Fixed the flaky test."
+            ),
+            Outcome::Success
+        );
+        assert_eq!(
+            turn(
+                &[("cargo test", "Exit code 101\n2 failed", true)],
+                "Two tests still fail."
+            ),
+            Outcome::Failure
+        );
+        assert_eq!(turn(&[], "Which database should I use?"), Outcome::Question);
+        assert_eq!(
+            turn(
+                &[(
+                    "rm -rf build",
+                    "The user doesn't want to proceed with this tool use.",
+                    true
+                )],
+                ""
+            ),
+            Outcome::Blocked
+        );
+    }
+
+    #[test]
+    fn test_strip_tag() {
+        assert_eq!(
+            Outcome::strip_tag("[failure] Claude Code could not fix the build."),
+            (
+                Some(Outcome::Failure),
+                "Claude Code could not fix the build."
+            )
+        );
+        assert_eq!(Outcome::strip_tag("[note] Done."), (None, "[note] Done."));
+        assert_eq!("Question".parse::<Outcome>().unwrap(), Outcome::Question);
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Bytes read from the end of the file at a time.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Non-empty lines of a file from last to first. The file is read in blocks
+/// from the end, so finding the latest entries costs the same however long
+/// the transcript has grown.
+pub struct ReverseLines<R> {
+    reader: R,
+    /// Offset where the part of the file not yet read ends
+    position: u64,
+    /// The earliest block read, cut back to the bytes not yet returned
+    buffer: Vec<u8>,
+    /// Blocks after `buffer` without a line break, the end of the line
+    /// being read; kept apart so a long line isn't copied once per block
+    tail: VecDeque<Vec<u8>>,
+    block_size: usize,
+}
+
+impl<R: Read + Seek> ReverseLines<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        Self::with_block_size(reader, BLOCK_SIZE)
+    }
+
+    fn with_block_size(mut reader: R, block_size: usize) -> io::Result<Self> {
+        let position = reader.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            reader,
+            position,
+            buffer: Vec::new(),
+            tail: VecDeque::new(),
+            block_size,
+        })
+    }
+
+    /// Move the buffer, which has no line break left, to the tail and read
+    /// the block before `position` into it.
+    fn read_block(&mut self) -> io::Result<()> {
+        let size = (self.block_size as u64).min(self.position);
+        self.position -= size;
+        let mut block = vec![0; size as usize];
+        self.reader.seek(SeekFrom::Start(self.position))?;
+        self.reader.read_exact(&mut block)?;
+        let previous = std::mem::replace(&mut self.buffer, block);
+        if !previous.is_empty() {
+            self.tail.push_front(previous);
+        }
+        Ok(())
+    }
+
+    /// The bytes from `start` in the buffer to the end of the tail.
+    fn take_line(&mut self, start: usize) -> Vec<u8> {
+        let mut line = self.buffer.split_off(start);
+        for block in self.tail.drain(..) {
+            line.extend_from_slice(&block);
+        }
+        line
+    }
+}
+
+impl<R: Read + Seek> Iterator for ReverseLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // The line after the last break is complete; before the first
+            // break it is only complete once the start of the file is read.
+            // Only the buffer is searched, as the tail holds no breaks.
+            let line = match self.buffer.iter().rposition(|byte| *byte == b'\n') {
+                Some(newline) => {
+                    let line = self.take_line(newline + 1);
+                    self.buffer.truncate(newline);
+                    line
+                }
+                None if self.position == 0 => self.take_line(0),
+                None => {
+                    if let Err(e) = self.read_block() {
+                        return Some(Err(e));
+                    }
+                    continue;
+                }
+            };
+
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\r');
+            if !line.trim().is_empty() {
+                return Some(Ok(line.to_string()));
+            }
+            if self.buffer.is_empty() && self.position == 0 {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn lines(text: &str, block_size: usize) -> Vec<String> {
+        ReverseLines::with_block_size(Cursor::new(text.as_bytes()), block_size)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lines_in_reverse_across_blocks() {
+        let text = "first line\r\n\nsécond, longer than a block\nthird\n\n";
+        for block_size in [1, 3, 7, 1024] {
+            assert_eq!(
+                lines(text, block_size),
+                ["third", "sécond, longer than a block", "first line"],
+                "block size {block_size}"
+            );
+        }
+        assert_eq!(lines("only", 2), ["only"]);
+        let long = "x".repeat(10_000);
+        assert_eq!(
+            lines(&format!("a\n{long}\nb"), 7),
+            ["b", long.as_str(), "a"]
+        );
+        assert!(lines("", 4).is_empty());
+        assert!(lines("\n\n", 1).is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use super::{ContentBlock, Entry};
+
+/// Characters of the user's request passed to the summarizer.
+const REQUEST_CHARS: usize = 1000;
+/// Characters of a tool call's target, e.g. a long shell command.
+const TARGET_CHARS: usize = 120;
+/// Tool calls listed before the rest are only counted.
+const MAX_TOOL_CALLS: usize = 40;
+
+/// Input fields naming what a tool call acted on, in order of preference.
+const TARGET_FIELDS: [&str; 8] = [
+    "file_path",
+    "notebook_path",
+    "command",
+    "pattern",
+    "url",
+    "query",
+    "path",
+    "description",
+];
+
+/// One tool call made during a turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub name: String,
+    pub input: Value,
+    /// Text of the matching tool result; `None` if none was recorded
+    pub output: Option<String>,
+    pub is_error: bool,
+}
+
+impl ToolCall {
+    /// The file, command or pattern the call acted on.
+    pub fn target(&self) -> Option<&str> {
+        TARGET_FIELDS
+            .iter()
+            .find_map(|field| self.input[field].as_str())
+            .filter(|target| !target.trim().is_empty())
+    }
+}
+
+/// Everything the agent did since the most recent prompt.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LastTurn {
+    /// What the user asked for
+    pub request: Option<String>,
+    /// Main-thread tool calls in the order they were made
+    pub tool_calls: Vec<ToolCall>,
+    /// The last thing the agent said
+    pub final_text: Option<String>,
+    /// Time from the prompt to the last recorded entry
+    pub duration: Option<Duration>,
+}
+
+impl LastTurn {
+    /// Build from the entries of one turn, e.g. from `read_last_turns(path, 1)`.
+    /// Subagent entries are skipped; their work shows up as the `Task` call.
+    pub fn from_entries(entries: &[Entry]) -> Self {
+        let mut builder = TurnBuilder::default();
+        for entry in entries {
+            if entry.is_prompt() {
+                let request = match entry {
+                    Entry::User(prompt) => Some(prompt.message.text()),
+                    _ => None,
+                };
+                builder.prompt(request, entry.timestamp());
+                continue;
+            }
+            let message = match entry {
+                Entry::User(entry) | Entry::Assistant(entry) => &entry.message,
+                _ => continue,
+            };
+            builder.seen(entry.timestamp());
+            if entry.is_sidechain() {
+                continue;
+            }
+
+            for block in message.blocks() {
+                match block {
+                    ContentBlock::ToolUse { id, name, input } => {
+                        builder.tool_call(
+                            Some(id),
+                            ToolCall {
+                                name: name.clone(),
+                                input: input.clone(),
+                                output: None,
+                                is_error: false,
+                            },
+                        );
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                    } => {
+                        let output = content.as_ref().map(|c| c.text()).unwrap_or_default();
+                        builder.tool_result(tool_use_id, output, *is_error);
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(assistant) = entry.as_assistant() {
+                builder.text(&assistant.message.text());
+            }
+        }
+        builder.finish()
+    }
+
+    /// Whether the agent neither said nor did anything.
+    pub fn is_empty(&self) -> bool {
+        self.tool_calls.is_empty() && self.final_text.is_none()
+    }
+
+    /// Text for the summarizer: the request, the tool calls with their
+    /// targets, and the final response.
+    pub fn summary_input(&self) -> String {
+        let mut sections = Vec::new();
+        if let Some(request) = &self.request {
+            sections.push(format!(
+                "User request:\n{}",
+                clip(request.trim(), REQUEST_CHARS)
+            ));
+        }
+
+        let mut lines: Vec<(String, usize)> = Vec::new();
+        for call in &self.tool_calls {
+            let line = match call.target() {
+                Some(target) => format!("{} {}", call.name, clip(target.trim(), TARGET_CHARS)),
+                None => call.name.clone(),
+            };
+            // Repeated calls, e.g. several edits to one file, are listed once
+            match lines.last_mut() {
+                Some((last, count)) if *last == line => *count += 1,
+                _ => lines.push((line, 1)),
+            }
+        }
+        if !lines.is_empty() {
+            let mut tools = String::from("Tool calls:");
+            for (line, count) in lines.iter().take(MAX_TOOL_CALLS) {
+                tools.push_str("\n- ");
+                tools.push_str(line);
+                if *count > 1 {
+                    tools.push_str(&format!(" ({count} times)"));
+                }
+            }
+            if lines.len() > MAX_TOOL_CALLS {
+                tools.push_str(&format!(
+                    "\n- ... and {} more",
+                    lines.len() - MAX_TOOL_CALLS
+                ));
+            }
+            sections.push(tools);
+        }
+
+        if let Some(text) = &self.final_text {
+            sections.push(format!("Final response:\n{}", text.trim()));
+        }
+        sections.join("\n\n")
+    }
+}
+
+/// Collects a `LastTurn` from the events of a transcript in file order, for
+/// every format; a prompt starts the turn over.
+#[derive(Debug, Default)]
+pub(super) struct TurnBuilder {
+    turn: LastTurn,
+    /// Tool calls made under each call id, to attach results
+    calls: HashMap<String, Range<usize>>,
+    started: Option<OffsetDateTime>,
+    last: Option<OffsetDateTime>,
+}
+
+impl TurnBuilder {
+    pub fn prompt(&mut self, request: Option<String>, at: Option<OffsetDateTime>) {
+        *self = TurnBuilder {
+            started: at,
+            last: at,
+            ..Default::default()
+        };
+        self.turn.request = request.filter(|text| !text.is_empty());
+    }
+
+    /// Note the time of an event, which extends the turn's duration.
+    pub fn seen(&mut self, at: Option<OffsetDateTime>) {
+        self.last = at.or(self.last);
+    }
+
+    pub fn tool_call(&mut self, id: Option<&str>, call: ToolCall) {
+        self.tool_calls(id, [call]);
+    }
+
+    /// Several calls made by one tool use, e.g. the files of a patch.
+    pub fn tool_calls(&mut self, id: Option<&str>, calls: impl IntoIterator<Item = ToolCall>) {
+        let start = self.turn.tool_calls.len();
+        self.turn.tool_calls.extend(calls);
+        if let Some(id) = id {
+            self.calls
+                .insert(id.to_string(), start..self.turn.tool_calls.len());
+        }
+    }
+
+    /// Attach a result to the calls made under `id`. Output a parser filled
+    /// in already, such as for a created file, is only replaced by an error.
+    pub fn tool_result(&mut self, id: &str, output: String, is_error: bool) {
+        let Some(range) = self.calls.get(id) else {
+            return;
+        };
+        for call in &mut self.turn.tool_calls[range.clone()] {
+            if call.output.is_none() || is_error {
+                call.output = Some(output.clone());
+            }
+            call.is_error = is_error;
+        }
+    }
+
+    /// Something the agent said; the last one is the final response.
+    pub fn text(&mut self, text: &str) {
+        if !text.trim().is_empty() {
+            self.turn.final_text = Some(text.to_string());
+        }
+    }
+
+    pub fn finish(mut self) -> LastTurn {
+        self.turn.duration = match (self.started, self.last) {
+            (Some(started), Some(last)) => Duration::try_from(last - started).ok(),
+            _ => None,
+        };
+        self.turn
+    }
+}
+
+/// `text` cut to `max_chars` characters, with "..." when shortened.
+fn clip(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(lines: &[&str]) -> Vec<Entry> {
+        lines.iter().filter_map(|line| Entry::parse(line)).collect()
+    }
+
+    #[test]
+    fn test_last_turn_summary_input() {
+        let turn = LastTurn::from_entries(&entries(&[
+            r#"{"type":"user","message":{"role":"user","content":"old task"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":"Old answer."}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"Fix the login tests"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me check."},{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"src/auth.rs"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fn login() {}"}]}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"role":"assistant","content":[{"type":"tool_use","id":"s1","name":"Grep","input":{"pattern":"login"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"src/auth.rs"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"src/auth.rs"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t4","name":"Bash","input":{"command":"cargo test","description":"Run tests"}},{"type":"tool_use","id":"t5","name":"TodoWrite","input":{"todos":[]}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done!"}]}}"#,
+        ]));
+
+        assert_eq!(turn.request.as_deref(), Some("Fix the login tests"));
+        assert_eq!(turn.tool_calls.len(), 5);
+        assert_eq!(turn.final_text.as_deref(), Some("Done!"));
+        assert_eq!(
+            turn.summary_input(),
+            "User request:\nFix the login tests\n\n\
+             Tool calls:\n\
+             - Read src/auth.rs\n\
+             - Edit src/auth.rs (2 times)\n\
+             - Bash cargo test\n\
+             - TodoWrite\n\n\
+             Final response:\nDone!"
+        );
+        assert!(LastTurn::from_entries(&[]).is_empty());
+    }
+}
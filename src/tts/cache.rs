@@ -0,0 +1,249 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+use super::{AudioFormat, SynthesizedAudio, TtsProvider};
+use crate::config::Config;
+
+/// Synthesized clips stored as `<sha256>.<ext>`. A clip's modification time
+/// is its last use, and the least recently used clips are evicted once the
+/// directory grows past `max_bytes`.
+#[derive(Debug, Clone)]
+pub struct AudioCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// Size of the audio cache, for `cache stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioCacheStats {
+    pub clips: usize,
+    pub bytes: u64,
+}
+
+struct Clip {
+    path: PathBuf,
+    bytes: u64,
+    used: SystemTime,
+}
+
+impl AudioCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// `None` when `audio_cache_max_mb` is 0.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.audio_cache_max_mb > 0).then(|| {
+            Self::new(
+                config.cache_dir.join("audio"),
+                config.audio_cache_max_mb * 1024 * 1024,
+            )
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Hash of everything that changes the audio: provider, voice, model,
+    /// settings and text.
+    pub fn key(provider: &dyn TtsProvider, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [provider.name(), &provider.voice_key(), text] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// The cached clip for `key` in one of the `playable` formats, marked as
+    /// just used.
+    pub fn get(&self, key: &str, playable: &[AudioFormat]) -> Option<SynthesizedAudio> {
+        playable.iter().find_map(|format| {
+            let path = self.path(key, *format);
+            let bytes = fs::read(&path).ok()?;
+            touch(&path);
+            debug!("Audio cache hit: {}", path.display());
+            Some(SynthesizedAudio {
+                bytes,
+                format: *format,
+            })
+        })
+    }
+
+    /// Store a clip, then evict old clips if the cache is over its limit.
+    pub fn insert(&self, key: &str, audio: &SynthesizedAudio) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.path(key, audio.format);
+        // Write then rename, so a concurrent reader never sees half a clip
+        let partial = path.with_extension("partial");
+        fs::write(&partial, &audio.bytes)
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        fs::rename(&partial, &path)?;
+        self.prune()?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> Result<AudioCacheStats> {
+        let clips = self.clips()?;
+        Ok(AudioCacheStats {
+            clips: clips.len(),
+            bytes: clips.iter().map(|clip| clip.bytes).sum(),
+        })
+    }
+
+    /// Evict least recently used clips until the cache fits in `max_bytes`.
+    /// Returns how many were removed.
+    pub fn prune(&self) -> Result<usize> {
+        let mut clips = self.clips()?;
+        let mut total: u64 = clips.iter().map(|clip| clip.bytes).sum();
+        clips.sort_by_key(|clip| clip.used);
+
+        let mut removed = 0;
+        for clip in clips {
+            if total <= self.max_bytes {
+                break;
+            }
+            match fs::remove_file(&clip.path) {
+                Ok(()) => {
+                    total -= clip.bytes;
+                    removed += 1;
+                }
+                Err(e) => warn!("Failed to evict {}: {}", clip.path.display(), e),
+            }
+        }
+        if removed > 0 {
+            info!("Evicted {} clips from the audio cache", removed);
+        }
+        Ok(removed)
+    }
+
+    /// Remove every clip. Returns how many were removed.
+    pub fn clear(&self) -> Result<usize> {
+        let clips = self.clips()?;
+        for clip in &clips {
+            fs::remove_file(&clip.path)
+                .with_context(|| format!("Failed to remove {}", clip.path.display()))?;
+        }
+        Ok(clips.len())
+    }
+
+    fn path(&self, key: &str, format: AudioFormat) -> PathBuf {
+        self.dir.join(format!("{key}.{}", format.extension()))
+    }
+
+    fn clips(&self) -> Result<Vec<Clip>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()))
+            }
+        };
+
+        let mut clips = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_clip = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(AudioFormat::from_extension)
+                .is_some();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if is_clip && metadata.is_file() {
+                clips.push(Clip {
+                    path,
+                    bytes: metadata.len(),
+                    used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+        Ok(clips)
+    }
+}
+
+/// Bump the modification time so LRU eviction sees the clip as used.
+fn touch(path: &Path) {
+    if let Err(e) = File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        debug!("Failed to touch {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn clip(size: usize) -> SynthesizedAudio {
+        SynthesizedAudio {
+            bytes: vec![0; size],
+            format: AudioFormat::Mp3,
+        }
+    }
+
+    fn age(cache: &AudioCache, key: &str, seconds: u64) {
+        let path = cache.path(key, AudioFormat::Mp3);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_get_matches_playable_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().join("audio"), 1024);
+        cache.insert("abc", &clip(10)).unwrap();
+
+        assert!(cache.get("abc", &[AudioFormat::Wav]).is_none());
+        let audio = cache
+            .get("abc", &[AudioFormat::Wav, AudioFormat::Mp3])
+            .unwrap();
+        assert_eq!(audio.format, AudioFormat::Mp3);
+        assert_eq!(audio.bytes.len(), 10);
+        assert!(cache.get("missing", &[AudioFormat::Mp3]).is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().to_path_buf(), 250);
+        cache.insert("old", &clip(100)).unwrap();
+        cache.insert("used", &clip(100)).unwrap();
+        age(&cache, "old", 60);
+        age(&cache, "used", 120);
+        // Reading makes "used" the most recent
+        cache.get("used", &[AudioFormat::Mp3]).unwrap();
+
+        cache.insert("new", &clip(100)).unwrap();
+        assert!(cache.get("old", &[AudioFormat::Mp3]).is_none());
+        assert!(cache.get("used", &[AudioFormat::Mp3]).is_some());
+        assert_eq!(
+            cache.stats().unwrap(),
+            AudioCacheStats {
+                clips: 2,
+                bytes: 200
+            }
+        );
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.stats().unwrap(), AudioCacheStats::default());
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use std::path::Path;
+use tracing::{debug, info};
+
+use super::{AudioFormat, SynthesizedAudio, TtsCapabilities, TtsProvider};
+
+#[derive(Debug, Serialize)]
+struct TextToSpeechRequest {
+    text: String,
+    model_id: String,
+    voice_settings: VoiceSettings,
+}
+
+#[derive(Debug, Serialize)]
+struct VoiceSettings {
+    stability: f32,
+    similarity_boost: f32,
+    style: f32,
+    use_speaker_boost: bool,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            stability: 0.5,
+            similarity_boost: 0.5,
+            style: 0.0,
+            use_speaker_boost: true,
+        }
+    }
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://api.elevenlabs.io";
+
+pub struct ElevenLabsClient {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    voice_id: String,
+    model_id: String,
+}
+
+impl ElevenLabsClient {
+    pub fn new(api_key: String, voice_id: String, model_id: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key,
+            voice_id,
+            model_id,
+        }
+    }
+
+    /// Talk to a proxy or stub server instead of the public API.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Cheap authenticated request confirming the API key is accepted.
+    pub async fn check_api_key(&self) -> Result<()> {
+        self.probe(&format!("{}/v1/user", self.base_url)).await
+    }
+
+    /// Confirm the configured voice ID exists for this account.
+    pub async fn check_voice(&self) -> Result<()> {
+        self.probe(&format!("{}/v1/voices/{}", self.base_url, self.voice_id))
+            .await
+    }
+
+    async fn probe(&self, url: &str) -> Result<()> {
+        let response = self
+            .client
+            .get(url)
+            .header("xi-api-key", &self.api_key)
+            .send()
+            .await
+            .context("Failed to reach ElevenLabs")?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("ElevenLabs returned {}: {}", status, error_text.trim());
+        }
+        Ok(())
+    }
+
+    pub async fn generate_speech(&self, text: &str, output_path: &Path) -> Result<()> {
+        let audio = self.request_speech(text).await?;
+        audio.write_to(output_path).await?;
+
+        // Debug: Check first few bytes to verify it's an MP3
+        let header = &audio.bytes[..audio.bytes.len().min(4)];
+        debug!("File header bytes: {:?} (hex: {:02x?})", header, header);
+
+        Ok(())
+    }
+
+    async fn request_speech(&self, text: &str) -> Result<SynthesizedAudio> {
+        let truncated_text = truncate_text(text);
+
+        info!(
+            "Generating speech with ElevenLabs for text: {}",
+            truncated_text
+        );
+
+        let url = format!("{}/v1/text-to-speech/{}", self.base_url, self.voice_id);
+
+        let request_body = TextToSpeechRequest {
+            text: truncated_text,
+            model_id: self.model_id.clone(),
+            voice_settings: VoiceSettings::default(),
+        };
+
+        debug!("Sending request to ElevenLabs API");
+        let response = self
+            .client
+            .post(&url)
+            .header("Accept", "audio/mpeg")
+            .header("Content-Type", "application/json")
+            .header("xi-api-key", &self.api_key)
+            .json(&request_body)
+            .send()
+            .await
+            .context("Failed to send request to ElevenLabs")?;
+
+        debug!("Response headers: {:?}", response.headers());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "ElevenLabs API error ({}): {}",
+                status,
+                error_text
+            ));
+        }
+
+        debug!("Downloading audio data");
+        let audio_data = response
+            .bytes()
+            .await
+            .context("Failed to download audio data")?;
+
+        Ok(SynthesizedAudio {
+            bytes: audio_data.to_vec(),
+            format: AudioFormat::Mp3,
+        })
+    }
+}
+
+/// Characters sent to ElevenLabs in one request.
+const MAX_TTS_LENGTH: usize = 1000;
+
+/// `text` cut to `MAX_TTS_LENGTH` characters, on a character boundary.
+fn truncate_text(text: &str) -> String {
+    match text.char_indices().nth(MAX_TTS_LENGTH) {
+        Some((end, _)) => {
+            info!(
+                "Text too long ({} chars), truncating to {} chars",
+                text.chars().count(),
+                MAX_TTS_LENGTH
+            );
+            format!("{}...", &text[..end])
+        }
+        None => text.to_string(),
+    }
+}
+
+#[async_trait]
+impl TtsProvider for ElevenLabsClient {
+    fn name(&self) -> &str {
+        "elevenlabs"
+    }
+
+    fn capabilities(&self) -> TtsCapabilities {
+        TtsCapabilities {
+            formats: vec![AudioFormat::Mp3],
+            // The whole response is downloaded before playback starts
+            streaming: false,
+            ssml: false,
+        }
+    }
+
+    fn voice_key(&self) -> String {
+        let settings = serde_json::to_string(&VoiceSettings::default()).unwrap_or_default();
+        format!("{}/{}/{}", self.voice_id, self.model_id, settings)
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio> {
+        self.request_speech(text).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_text_on_char_boundary() {
+        // A two-byte 'é' straddles byte 1000
+        let text = format!("{}é{}", "a".repeat(MAX_TTS_LENGTH - 1), "b".repeat(10));
+        let truncated = truncate_text(&text);
+        assert_eq!(truncated, format!("{}é...", "a".repeat(MAX_TTS_LENGTH - 1)));
+
+        let accents = "é".repeat(MAX_TTS_LENGTH);
+        assert_eq!(truncate_text(&accents), accents);
+        assert_eq!(truncate_text("short"), "short");
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use super::{AudioFormat, SynthesizedAudio, TtsCapabilities, TtsProvider};
+
+/// Offline neural TTS using a Piper ONNX voice model.
+pub struct PiperProvider {
+    binary: PathBuf,
+    model: PathBuf,
+}
+
+impl PiperProvider {
+    pub fn new(binary: PathBuf, model: PathBuf) -> Self {
+        Self { binary, model }
+    }
+
+    /// Returns `None` unless `binary` is on PATH and the model file exists.
+    pub fn detect(binary: &str, model: Option<&PathBuf>) -> Option<Self> {
+        let model = model.filter(|model| model.is_file())?;
+        let binary = which::which(binary).ok()?;
+        Some(Self::new(binary, model.clone()))
+    }
+
+    /// Piper reads the text on stdin and writes WAV to `output`.
+    fn command(&self, output: &Path) -> Command {
+        let mut command = Command::new(&self.binary);
+        command
+            .arg("--model")
+            .arg(&self.model)
+            .arg("--output_file")
+            .arg(output)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        command
+    }
+}
+
+#[async_trait]
+impl TtsProvider for PiperProvider {
+    fn name(&self) -> &str {
+        "piper"
+    }
+
+    fn capabilities(&self) -> TtsCapabilities {
+        TtsCapabilities {
+            formats: vec![AudioFormat::Wav],
+            streaming: false,
+            ssml: false,
+        }
+    }
+
+    fn voice_key(&self) -> String {
+        self.model.display().to_string()
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio> {
+        info!(
+            "Generating speech with Piper model: {}",
+            self.model.display()
+        );
+
+        // Piper reads text on stdin and can only write WAV to a file
+        let output = tempfile::Builder::new()
+            .prefix("voice_notifier_piper_")
+            .suffix(".wav")
+            .tempfile()
+            .context("Failed to create Piper output file")?;
+
+        let mut child = self
+            .command(output.path())
+            .spawn()
+            .context("Failed to spawn piper")?;
+
+        let mut stdin = child.stdin.take().context("Failed to open piper stdin")?;
+        stdin.write_all(text.as_bytes()).await?;
+        drop(stdin);
+
+        let result = child.wait_with_output().await?;
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            return Err(anyhow::anyhow!("piper failed: {}", stderr));
+        }
+
+        let bytes = tokio::fs::read(output.path())
+            .await
+            .context("Failed to read Piper output")?;
+        debug!("Piper produced {} bytes", bytes.len());
+
+        Ok(SynthesizedAudio {
+            bytes,
+            format: AudioFormat::Wav,
+        })
+    }
+}
+
+/// Formant TTS via espeak-ng; robotic but available almost everywhere.
+pub struct EspeakProvider {
+    binary: PathBuf,
+    voice: Option<String>,
+}
+
+impl EspeakProvider {
+    pub fn new(binary: PathBuf, voice: Option<String>) -> Self {
+        Self { binary, voice }
+    }
+
+    /// Prefers `espeak-ng`, falling back to the original `espeak`.
+    pub fn detect(voice: Option<String>) -> Option<Self> {
+        ["espeak-ng", "espeak"]
+            .iter()
+            .find_map(|binary| which::which(binary).ok())
+            .map(|binary| Self::new(binary, voice))
+    }
+
+    /// espeak writes WAV to stdout; the text is plain, not SSML.
+    fn command(&self, text: &str) -> Command {
+        let mut command = Command::new(&self.binary);
+        command.arg("--stdout");
+        if let Some(voice) = &self.voice {
+            command.arg("-v").arg(voice);
+        }
+        // "--" keeps text starting with '-' from being read as a flag
+        command.arg("--").arg(text);
+        command
+    }
+}
+
+#[async_trait]
+impl TtsProvider for EspeakProvider {
+    fn name(&self) -> &str {
+        "espeak"
+    }
+
+    fn capabilities(&self) -> TtsCapabilities {
+        TtsCapabilities {
+            formats: vec![AudioFormat::Wav],
+            streaming: false,
+            ssml: false,
+        }
+    }
+
+    fn voice_key(&self) -> String {
+        self.voice.clone().unwrap_or_default()
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio> {
+        info!("Generating speech with {}", self.binary.display());
+
+        let output = self
+            .command(text)
+            .output()
+            .await
+            .context("Failed to execute espeak")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("espeak failed: {}", stderr));
+        }
+
+        Ok(SynthesizedAudio {
+            bytes: output.stdout,
+            format: AudioFormat::Wav,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_piper_command() {
+        let piper = PiperProvider::new(
+            PathBuf::from("/usr/bin/piper"),
+            PathBuf::from("/voices/en_US-amy-medium.onnx"),
+        );
+        let command = piper.command(Path::new("/tmp/voice_notifier_piper_1.wav"));
+        assert_eq!(command.as_std().get_program(), "/usr/bin/piper");
+        assert_eq!(
+            args(&command),
+            [
+                "--model",
+                "/voices/en_US-amy-medium.onnx",
+                "--output_file",
+                "/tmp/voice_notifier_piper_1.wav"
+            ]
+        );
+    }
+
+    /// A stand-in piper that copies stdin to `--output_file`, so the output
+    /// is the text it was sent.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_piper_sends_text_on_stdin_and_reads_output_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("piper");
+        std::fs::write(&binary, "#!/bin/sh\ncat > \"$4\"\n").unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let piper = PiperProvider::new(binary, dir.path().join("voice.onnx"));
+        let audio = piper.synthesize("-- Tests pass").await.unwrap();
+        assert_eq!(audio.bytes, b"-- Tests pass");
+        assert_eq!(audio.format, AudioFormat::Wav);
+    }
+
+    #[test]
+    fn test_espeak_command() {
+        let espeak = EspeakProvider::new(PathBuf::from("espeak-ng"), Some("en-us".to_string()));
+        assert_eq!(
+            args(&espeak.command("-1 tests failed")),
+            ["--stdout", "-v", "en-us", "--", "-1 tests failed"]
+        );
+        let espeak = EspeakProvider::new(PathBuf::from("espeak-ng"), None);
+        assert_eq!(
+            args(&espeak.command("<speak>Done</speak>")),
+            ["--stdout", "--", "<speak>Done</speak>"]
+        );
+        assert!(!espeak.capabilities().ssml);
+    }
+}
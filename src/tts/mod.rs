@@ -0,0 +1,442 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+
+mod cache;
+pub mod elevenlabs;
+mod local;
+mod openai;
+
+pub use cache::{AudioCache, AudioCacheStats};
+pub use elevenlabs::ElevenLabsClient;
+pub use local::{EspeakProvider, PiperProvider};
+pub use openai::OpenAiTtsClient;
+
+/// Container format of synthesized audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    Wav,
+    Ogg,
+    Opus,
+    Flac,
+    Aac,
+}
+
+impl AudioFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Aac => "aac",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "mp3" => Some(AudioFormat::Mp3),
+            "wav" => Some(AudioFormat::Wav),
+            "ogg" => Some(AudioFormat::Ogg),
+            "opus" => Some(AudioFormat::Opus),
+            "flac" => Some(AudioFormat::Flac),
+            "aac" => Some(AudioFormat::Aac),
+            _ => None,
+        }
+    }
+}
+
+/// What a provider can produce, so the pipeline can pick one the player handles.
+#[derive(Debug, Clone)]
+pub struct TtsCapabilities {
+    pub formats: Vec<AudioFormat>,
+    /// Audio can be played while it is still being received
+    pub streaming: bool,
+    pub ssml: bool,
+}
+
+impl TtsCapabilities {
+    pub fn supports_any(&self, formats: &[AudioFormat]) -> bool {
+        self.formats.iter().any(|format| formats.contains(format))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SynthesizedAudio {
+    pub bytes: Vec<u8>,
+    pub format: AudioFormat,
+}
+
+impl SynthesizedAudio {
+    pub async fn write_to(&self, output_path: &Path) -> Result<()> {
+        debug!("Writing audio to file: {}", output_path.display());
+        tokio::fs::write(output_path, &self.bytes)
+            .await
+            .context("Failed to write audio data")?;
+        info!(
+            "Successfully wrote speech file: {} (size: {} bytes)",
+            output_path.display(),
+            self.bytes.len()
+        );
+        Ok(())
+    }
+}
+
+/// A text-to-speech engine.
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    /// Name used in the `TTS_PROVIDERS` list and in logs.
+    fn name(&self) -> &str;
+
+    fn capabilities(&self) -> TtsCapabilities;
+
+    /// Voice, model and settings that shape the audio. Together with the
+    /// provider name and the text it keys the audio cache.
+    fn voice_key(&self) -> String;
+
+    async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio>;
+}
+
+/// Result of warming the audio cache with one provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warmed {
+    AlreadyCached,
+    Synthesized,
+}
+
+/// Ordered list of providers tried one after another until one succeeds.
+#[derive(Default)]
+pub struct TtsChain {
+    providers: Vec<Box<dyn TtsProvider>>,
+    cache: Option<AudioCache>,
+}
+
+impl TtsChain {
+    pub fn new(providers: Vec<Box<dyn TtsProvider>>) -> Self {
+        Self {
+            providers,
+            cache: None,
+        }
+    }
+
+    /// Reuse clips a provider already synthesized for the same text.
+    pub fn with_cache(mut self, cache: AudioCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Build the chain from `config.tts_providers`, skipping providers
+    /// that are unknown or missing required configuration.
+    pub fn from_config(config: &Config) -> Self {
+        let mut providers: Vec<Box<dyn TtsProvider>> = Vec::new();
+        for name in &config.tts_providers {
+            match build_provider(name, config) {
+                Some(provider) => providers.push(provider),
+                None => debug!("TTS provider '{}' is not available", name),
+            }
+        }
+        let chain = Self::new(providers);
+        match AudioCache::from_config(config) {
+            Some(cache) => chain.with_cache(cache),
+            None => chain,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub fn providers(&self) -> impl Iterator<Item = &dyn TtsProvider> {
+        self.providers.iter().map(|provider| provider.as_ref())
+    }
+
+    /// Synthesize with the first provider that succeeds and produces one of
+    /// the `playable` formats, using its cached clip when there is one.
+    pub async fn synthesize(
+        &self,
+        text: &str,
+        playable: &[AudioFormat],
+    ) -> Result<SynthesizedAudio> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            if !provider.capabilities().supports_any(playable) {
+                debug!(
+                    "Skipping TTS provider '{}': no playable output format",
+                    provider.name()
+                );
+                continue;
+            }
+
+            let key = AudioCache::key(provider.as_ref(), text);
+            if let Some(audio) = self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get(&key, playable))
+            {
+                info!("Using cached speech from '{}'", provider.name());
+                return Ok(audio);
+            }
+
+            info!("Synthesizing speech with '{}'", provider.name());
+            match provider.synthesize(text).await {
+                Ok(audio) if playable.contains(&audio.format) => {
+                    if let Some(cache) = &self.cache {
+                        if let Err(e) = cache.insert(&key, &audio) {
+                            warn!("Failed to cache speech: {:#}", e);
+                        }
+                    }
+                    return Ok(audio);
+                }
+                Ok(audio) => {
+                    warn!(
+                        "TTS provider '{}' returned unplayable format {:?}",
+                        provider.name(),
+                        audio.format
+                    );
+                }
+                Err(e) => {
+                    error!("TTS provider '{}' failed: {:#}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No TTS provider available")))
+    }
+
+    /// Cache `text` from every provider, not just the first, so it plays
+    /// from the cache whichever provider is unreachable later. Providers
+    /// without a `playable` format are skipped, as `synthesize` would.
+    pub async fn warm(
+        &self,
+        text: &str,
+        playable: &[AudioFormat],
+    ) -> Result<Vec<(&str, Result<Warmed>)>> {
+        let cache = self
+            .cache
+            .as_ref()
+            .context("The audio cache is disabled (audio_cache_max_mb = 0)")?;
+
+        let mut results = Vec::new();
+        for provider in &self.providers {
+            if !provider.capabilities().supports_any(playable) {
+                debug!(
+                    "Not warming TTS provider '{}': no playable output format",
+                    provider.name()
+                );
+                continue;
+            }
+
+            let key = AudioCache::key(provider.as_ref(), text);
+            let result = if cache.get(&key, playable).is_some() {
+                Ok(Warmed::AlreadyCached)
+            } else {
+                match provider.synthesize(text).await {
+                    Ok(audio) if playable.contains(&audio.format) => {
+                        cache.insert(&key, &audio).map(|()| Warmed::Synthesized)
+                    }
+                    Ok(audio) => Err(anyhow::anyhow!(
+                        "Returned unplayable format {:?}",
+                        audio.format
+                    )),
+                    Err(e) => Err(e),
+                }
+            };
+            results.push((provider.name(), result));
+        }
+        Ok(results)
+    }
+}
+
+/// Names accepted in `tts_providers`.
+pub const PROVIDER_NAMES: [&str; 5] = ["elevenlabs", "piper", "espeak", "espeak-ng", "openai"];
+
+fn build_provider(name: &str, config: &Config) -> Option<Box<dyn TtsProvider>> {
+    match name {
+        "elevenlabs" => config.eleven_labs_api_key.as_ref().map(|api_key| {
+            Box::new(
+                ElevenLabsClient::new(
+                    api_key.clone(),
+                    config.eleven_labs_voice_id.clone(),
+                    config.eleven_labs_model_id.clone(),
+                )
+                .with_base_url(&config.eleven_labs_base_url),
+            ) as Box<dyn TtsProvider>
+        }),
+        "piper" => PiperProvider::detect(&config.piper_binary, config.piper_model.as_ref())
+            .map(|provider| Box::new(provider) as Box<dyn TtsProvider>),
+        "espeak" | "espeak-ng" => EspeakProvider::detect(config.espeak_voice.clone())
+            .map(|provider| Box::new(provider) as Box<dyn TtsProvider>),
+        "openai" => build_openai_provider(config),
+        _ => {
+            warn!("Unknown TTS provider '{}'", name);
+            None
+        }
+    }
+}
+
+fn build_openai_provider(config: &Config) -> Option<Box<dyn TtsProvider>> {
+    // Needs either a key for the hosted API or an explicit self-hosted server
+    if config.openai_tts_api_key.is_none() && config.openai_tts_base_url.is_none() {
+        return None;
+    }
+
+    let Some(format) = AudioFormat::from_extension(&config.openai_tts_format) else {
+        warn!(
+            "Unsupported OpenAI TTS response format '{}'",
+            config.openai_tts_format
+        );
+        return None;
+    };
+
+    Some(Box::new(OpenAiTtsClient::new(
+        config
+            .openai_tts_base_url
+            .clone()
+            .unwrap_or_else(|| openai::DEFAULT_BASE_URL.to_string()),
+        config.openai_tts_api_key.clone(),
+        config.openai_tts_model.clone(),
+        config.openai_tts_voice.clone(),
+        format,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeProvider {
+        name: &'static str,
+        format: AudioFormat,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl TtsProvider for FakeProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn capabilities(&self) -> TtsCapabilities {
+            TtsCapabilities {
+                formats: vec![self.format],
+                streaming: false,
+                ssml: false,
+            }
+        }
+
+        fn voice_key(&self) -> String {
+            format!("{:?}", self.format)
+        }
+
+        async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio> {
+            if self.fail {
+                anyhow::bail!("{} is down", self.name);
+            }
+            Ok(SynthesizedAudio {
+                bytes: text.as_bytes().to_vec(),
+                format: self.format,
+            })
+        }
+    }
+
+    fn fake(name: &'static str, format: AudioFormat, fail: bool) -> Box<dyn TtsProvider> {
+        Box::new(FakeProvider { name, format, fail })
+    }
+
+    #[tokio::test]
+    async fn test_chain_falls_through_failures() {
+        let chain = TtsChain::new(vec![
+            fake("broken", AudioFormat::Mp3, true),
+            fake("working", AudioFormat::Wav, false),
+        ]);
+        let audio = chain
+            .synthesize("hello", &[AudioFormat::Mp3, AudioFormat::Wav])
+            .await
+            .unwrap();
+        assert_eq!(audio.format, AudioFormat::Wav);
+        assert_eq!(audio.bytes, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_chain_skips_unplayable_formats() {
+        let chain = TtsChain::new(vec![
+            fake("opus-only", AudioFormat::Opus, false),
+            fake("mp3", AudioFormat::Mp3, false),
+        ]);
+        let audio = chain.synthesize("hi", &[AudioFormat::Mp3]).await.unwrap();
+        assert_eq!(audio.format, AudioFormat::Mp3);
+    }
+
+    #[tokio::test]
+    async fn test_chain_reuses_cached_speech() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().to_path_buf(), 1024 * 1024);
+        let chain =
+            TtsChain::new(vec![fake("mp3", AudioFormat::Mp3, false)]).with_cache(cache.clone());
+        chain
+            .synthesize("hello", &[AudioFormat::Mp3])
+            .await
+            .unwrap();
+        assert_eq!(cache.stats().unwrap().clips, 1);
+
+        // The same provider and voice is served from the cache even when it fails
+        let offline = TtsChain::new(vec![fake("mp3", AudioFormat::Mp3, true)]).with_cache(cache);
+        let audio = offline
+            .synthesize("hello", &[AudioFormat::Mp3])
+            .await
+            .unwrap();
+        assert_eq!(audio.bytes, b"hello");
+        assert!(offline
+            .synthesize("other", &[AudioFormat::Mp3])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_warm_caches_every_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().to_path_buf(), 1024 * 1024);
+        let chain = TtsChain::new(vec![
+            fake("mp3", AudioFormat::Mp3, false),
+            fake("broken", AudioFormat::Mp3, true),
+            fake("wav", AudioFormat::Wav, false),
+        ])
+        .with_cache(cache.clone());
+
+        let playable = [AudioFormat::Mp3, AudioFormat::Wav];
+        let results = chain
+            .warm("Droid needs your attention", &playable)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].1.as_ref().unwrap(), &Warmed::Synthesized);
+        assert!(results[1].1.is_err());
+        assert_eq!(results[2].1.as_ref().unwrap(), &Warmed::Synthesized);
+        assert_eq!(cache.stats().unwrap().clips, 2);
+
+        let again = chain
+            .warm("Droid needs your attention", &playable)
+            .await
+            .unwrap();
+        assert_eq!(again[0].1.as_ref().unwrap(), &Warmed::AlreadyCached);
+        assert!(TtsChain::default().warm("hi", &playable).await.is_err());
+
+        // Only providers the player can play are warmed
+        let wav_only = chain.warm("Fixed", &[AudioFormat::Wav]).await.unwrap();
+        assert_eq!(wav_only.len(), 1);
+        assert_eq!(wav_only[0].0, "wav");
+    }
+
+    #[tokio::test]
+    async fn test_empty_chain_errors() {
+        let chain = TtsChain::default();
+        assert!(chain.synthesize("hi", &[AudioFormat::Mp3]).await.is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::{AudioFormat, SynthesizedAudio, TtsCapabilities, TtsProvider};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Long enough for a self-hosted server synthesizing on the CPU, short
+/// enough that a stalled one doesn't hold up the hook or the daemon.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+}
+
+/// Client for the OpenAI `/v1/audio/speech` protocol, also spoken by
+/// self-hosted servers such as Kokoro and LocalAI.
+pub struct OpenAiTtsClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    voice: String,
+    format: AudioFormat,
+}
+
+impl OpenAiTtsClient {
+    pub fn new(
+        base_url: String,
+        api_key: Option<String>,
+        model: String,
+        voice: String,
+        format: AudioFormat,
+    ) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            voice,
+            format,
+        }
+    }
+}
+
+#[async_trait]
+impl TtsProvider for OpenAiTtsClient {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn capabilities(&self) -> TtsCapabilities {
+        TtsCapabilities {
+            formats: vec![self.format],
+            // The response is buffered in full before it is played
+            streaming: false,
+            ssml: false,
+        }
+    }
+
+    /// Includes the server, since self-hosted voices share names like "alloy".
+    fn voice_key(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.base_url,
+            self.model,
+            self.voice,
+            self.format.extension()
+        )
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<SynthesizedAudio> {
+        info!(
+            "Generating speech with {} (model: {}, voice: {})",
+            self.base_url, self.model, self.voice
+        );
+
+        let request_body = SpeechRequest {
+            model: &self.model,
+            input: text,
+            voice: &self.voice,
+            response_format: self.format.extension(),
+        };
+
+        let mut request = self
+            .client
+            .post(format!("{}/audio/speech", self.base_url))
+            .json(&request_body);
+        // Self-hosted servers usually don't need a key
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        debug!("Sending request to OpenAI-compatible speech API");
+        let response = request
+            .send()
+            .await
+            .context("Failed to send request to speech API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "Speech API error ({}): {}",
+                status,
+                error_text
+            ));
+        }
+
+        let audio_data = response
+            .bytes()
+            .await
+            .context("Failed to download audio data")?;
+
+        Ok(SynthesizedAudio {
+            bytes: audio_data.to_vec(),
+            format: self.format,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_synthesize_against_stub_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/audio/speech")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "model": "kokoro",
+                "input": "Build finished",
+                "voice": "af_sky",
+                "response_format": "wav",
+            })))
+            .with_status(200)
+            .with_body(b"RIFF")
+            .create_async()
+            .await;
+
+        let client = OpenAiTtsClient::new(
+            format!("{}/v1/", server.url()),
+            Some("test-key".to_string()),
+            "kokoro".to_string(),
+            "af_sky".to_string(),
+            AudioFormat::Wav,
+        );
+        let audio = client.synthesize("Build finished").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(audio.format, AudioFormat::Wav);
+        assert_eq!(audio.bytes, b"RIFF");
+    }
+
+    #[tokio::test]
+    async fn test_synthesize_reports_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/audio/speech")
+            .with_status(500)
+            .with_body("model not loaded")
+            .create_async()
+            .await;
+
+        let client = OpenAiTtsClient::new(
+            server.url(),
+            None,
+            "tts-1".to_string(),
+            "alloy".to_string(),
+            AudioFormat::Mp3,
+        );
+        let error = client.synthesize("hi").await.unwrap_err();
+        assert!(error.to_string().contains("model not loaded"));
+    }
+}